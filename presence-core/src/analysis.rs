@@ -0,0 +1,1328 @@
+use calamine::{Data, Reader, Xlsx};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use csv::StringRecord;
+use encoding_rs::{UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use crate::columns::{self, ColumnSpec};
+use crate::error::{PresenceError, Result};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttendanceConfig {
+    pub class_start: String,
+    pub class_end: String,
+    pub late_minutes: String,
+    pub absent_minutes: String,
+    pub total_points: String,
+    pub late_penalty: String,
+    /// Minimum attendance rate, in percent, required for eligibility.
+    pub min_attendance: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Txt,
+    Pdf,
+    Moodle,
+    Canvas,
+    Blackboard,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 6] = [
+        ReportFormat::Csv,
+        ReportFormat::Txt,
+        ReportFormat::Pdf,
+        ReportFormat::Moodle,
+        ReportFormat::Canvas,
+        ReportFormat::Blackboard,
+    ];
+
+    /// Short identifier used on the command line.
+    pub fn key(self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv",
+            ReportFormat::Txt => "txt",
+            ReportFormat::Pdf => "pdf",
+            ReportFormat::Moodle => "moodle",
+            ReportFormat::Canvas => "canvas",
+            ReportFormat::Blackboard => "blackboard",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Txt => "txt",
+            ReportFormat::Pdf => "pdf",
+            _ => "csv",
+        }
+    }
+
+    /// File name used when several formats are exported side by side.
+    pub fn file_name(self, stem: &str) -> String {
+        match self {
+            ReportFormat::Csv | ReportFormat::Txt | ReportFormat::Pdf => {
+                format!("{stem}.{}", self.extension())
+            }
+            ReportFormat::Moodle => format!("{stem}-moodle.csv"),
+            ReportFormat::Canvas => format!("{stem}-canvas.csv"),
+            ReportFormat::Blackboard => format!("{stem}-blackboard.csv"),
+        }
+    }
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ReportFormat::Csv => "CSV",
+            ReportFormat::Txt => "Text",
+            ReportFormat::Pdf => "PDF",
+            ReportFormat::Moodle => "Moodle",
+            ReportFormat::Canvas => "Canvas",
+            ReportFormat::Blackboard => "Blackboard",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// Presentation settings shared by the report writers.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub course_name: String,
+    pub term: String,
+    /// Append one page per student to PDF reports.
+    pub student_pages: bool,
+    /// Columns written by every format, in order.
+    pub columns: Vec<ColumnSpec>,
+    /// Name of the grade item in LMS gradebook exports.
+    pub grade_item: String,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            course_name: String::new(),
+            term: String::new(),
+            student_pages: false,
+            columns: columns::default_columns(),
+            grade_item: "Attendance".to_string(),
+        }
+    }
+}
+
+impl ExportOptions {
+    /// File name stem derived from the course and term, e.g. `cs101-fall-2023-attendance`.
+    pub fn file_stem(&self) -> String {
+        let mut stem = String::new();
+        for word in format!("{} {} attendance", self.course_name, self.term)
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            if !stem.is_empty() {
+                stem.push('-');
+            }
+            stem.push_str(&word.to_lowercase());
+        }
+        stem
+    }
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = PresenceError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        ReportFormat::ALL
+            .into_iter()
+            .find(|format| format.key().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| {
+                let keys: Vec<&str> = ReportFormat::ALL.iter().map(|f| f.key()).collect();
+                PresenceError::config(
+                    "format",
+                    format!("Unknown format '{value}'. Use one of: {}.", keys.join(", ")),
+                )
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AttendanceReport {
+    pub students: Vec<StudentRecord>,
+    pub sessions: usize,
+    pub total_points: f32,
+    /// Date of each processed session, in processing order.
+    pub session_dates: Vec<NaiveDate>,
+    /// Export each session was read from, aligned with `session_dates`. Empty
+    /// when the report was generated from participant lists directly.
+    pub session_sources: Vec<PathBuf>,
+    pub config: ConfigValues,
+}
+
+impl AttendanceReport {
+    /// First and last session date, if any session was processed.
+    pub fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let first = self.session_dates.iter().min()?;
+        let last = self.session_dates.iter().max()?;
+        Some((*first, *last))
+    }
+
+    pub fn is_eligible(&self, student: &StudentRecord) -> bool {
+        student.attendance_rate() >= self.config.min_attendance
+    }
+
+    /// Eligible now, but one more absence would drop the student below the
+    /// minimum attendance.
+    pub fn is_at_risk(&self, student: &StudentRecord) -> bool {
+        let attended = (student.normal + student.late) as f32;
+        let total = (student.normal + student.late + student.absent + 1) as f32;
+        self.is_eligible(student) && attended / total < self.config.min_attendance
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StudentRecord {
+    pub name: String,
+    pub surname: String,
+    pub id: String,
+    pub email: String,
+    pub section: String,
+    pub normal: u32,
+    pub late: u32,
+    pub absent: u32,
+    pub score: f32,
+    /// One entry per processed session, aligned with `AttendanceReport::session_dates`.
+    pub history: Vec<SessionAttendance>,
+}
+
+impl StudentRecord {
+    /// Share of sessions attended, counting late arrivals as present.
+    pub fn attendance_rate(&self) -> f32 {
+        let total = self.normal + self.late + self.absent;
+        if total == 0 {
+            return 0.0;
+        }
+        (self.normal + self.late) as f32 / total as f32
+    }
+
+    /// Identifies the student across sessions: the ID, else the email, else
+    /// the full name.
+    pub fn key(&self) -> String {
+        student_key(&self.id, &self.email, &self.name, &self.surname)
+    }
+
+    /// Joined without an email address, e.g. as an unauthenticated guest.
+    pub fn is_guest(&self) -> bool {
+        self.email.is_empty()
+    }
+
+    /// Whether any session status was set manually.
+    pub fn has_overrides(&self) -> bool {
+        self.history
+            .iter()
+            .any(|session| session.original.is_some())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttendanceStatus {
+    Normal,
+    Late,
+    Absent,
+}
+
+impl AttendanceStatus {
+    pub const ALL: [AttendanceStatus; 3] = [
+        AttendanceStatus::Normal,
+        AttendanceStatus::Late,
+        AttendanceStatus::Absent,
+    ];
+}
+
+impl std::fmt::Display for AttendanceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            AttendanceStatus::Normal => "Normal",
+            AttendanceStatus::Late => "Late",
+            AttendanceStatus::Absent => "Absent",
+        };
+        write!(f, "{label}")
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionAttendance {
+    pub date: NaiveDate,
+    pub status: AttendanceStatus,
+    /// `None` when the student did not join the session at all.
+    pub first_join: Option<NaiveDateTime>,
+    pub minutes_late: i64,
+    /// Status computed from the export when a manual override replaced it,
+    /// see `overrides::apply_overrides`.
+    pub original: Option<AttendanceStatus>,
+}
+
+impl SessionAttendance {
+    fn missed(date: NaiveDate) -> Self {
+        Self {
+            date,
+            status: AttendanceStatus::Absent,
+            first_join: None,
+            minutes_late: 0,
+            original: None,
+        }
+    }
+}
+
+/// Class-wide statistics derived from a report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassSummary {
+    pub students: usize,
+    pub sessions: usize,
+    pub mean_rate: f32,
+    pub median_rate: f32,
+    pub min_rate: f32,
+    pub max_rate: f32,
+    pub mean_score: f32,
+    pub eligible: usize,
+    /// Number of students per attendance-rate decile (0-10%, 10-20%, ..., 90-100%).
+    pub rate_histogram: [usize; 10],
+    /// Eligible students one absence away from losing eligibility.
+    pub at_risk: usize,
+    /// Share of students who attended each session, on time or late, aligned
+    /// with `AttendanceReport::session_dates`.
+    pub session_rates: Vec<f32>,
+    /// Number of students per score decile of the total points.
+    pub score_histogram: [usize; 10],
+    /// Joins per [`LATENESS_BUCKET_MINUTES`] after class start; the last bucket
+    /// holds every later join.
+    pub lateness_histogram: [usize; 12],
+}
+
+/// Width of a bucket of `ClassSummary::lateness_histogram`, in minutes.
+pub const LATENESS_BUCKET_MINUTES: i64 = 5;
+
+/// Decile (0-9) of `value` within `0.0..=1.0`.
+fn decile(value: f32) -> usize {
+    ((value * 10.0).max(0.0) as usize).min(9)
+}
+
+pub fn summarize(report: &AttendanceReport) -> ClassSummary {
+    let mut rates: Vec<f32> = report
+        .students
+        .iter()
+        .map(StudentRecord::attendance_rate)
+        .collect();
+    rates.sort_by(|a, b| a.total_cmp(b));
+    let count = rates.len();
+    let mut rate_histogram = [0usize; 10];
+    for rate in &rates {
+        rate_histogram[decile(*rate)] += 1;
+    }
+    let mut score_histogram = [0usize; 10];
+    if report.total_points > 0.0 {
+        for student in &report.students {
+            score_histogram[decile(student.score / report.total_points)] += 1;
+        }
+    }
+    let mut lateness_histogram = [0usize; 12];
+    let joins = report
+        .students
+        .iter()
+        .flat_map(|student| &student.history)
+        .filter(|session| session.first_join.is_some());
+    for session in joins {
+        let bucket = (session.minutes_late / LATENESS_BUCKET_MINUTES) as usize;
+        lateness_histogram[bucket.min(lateness_histogram.len() - 1)] += 1;
+    }
+    let session_rates = (0..report.sessions)
+        .map(|index| {
+            let attended = report
+                .students
+                .iter()
+                .filter(|student| {
+                    student
+                        .history
+                        .get(index)
+                        .is_some_and(|session| session.status != AttendanceStatus::Absent)
+                })
+                .count();
+            if count == 0 {
+                0.0
+            } else {
+                attended as f32 / count as f32
+            }
+        })
+        .collect();
+    let median_rate = match count {
+        0 => 0.0,
+        _ if count.is_multiple_of(2) => (rates[count / 2 - 1] + rates[count / 2]) / 2.0,
+        _ => rates[count / 2],
+    };
+    let mean = |sum: f32| if count == 0 { 0.0 } else { sum / count as f32 };
+    ClassSummary {
+        students: count,
+        sessions: report.sessions,
+        mean_rate: mean(rates.iter().sum()),
+        median_rate,
+        min_rate: rates.first().copied().unwrap_or(0.0),
+        max_rate: rates.last().copied().unwrap_or(0.0),
+        mean_score: mean(report.students.iter().map(|student| student.score).sum()),
+        eligible: report
+            .students
+            .iter()
+            .filter(|student| report.is_eligible(student))
+            .count(),
+        rate_histogram,
+        at_risk: report
+            .students
+            .iter()
+            .filter(|student| report.is_at_risk(student))
+            .count(),
+        session_rates,
+        score_histogram,
+        lateness_histogram,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Participant {
+    pub name: String,
+    pub surname: String,
+    pub id: String,
+    pub email: String,
+    pub section: String,
+    pub first_join: NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigValues {
+    pub class_start: NaiveTime,
+    pub class_end: NaiveTime,
+    pub late_minutes: i64,
+    pub absent_minutes: i64,
+    pub total_points: f32,
+    pub late_penalty: f32,
+    /// Fraction of sessions (0.0-1.0) a student must attend to be eligible.
+    pub min_attendance: f32,
+}
+
+/// Parses the configuration, failing with the first problem found. Use
+/// [`validate_config`] to list every problem at once.
+pub fn parse_config(config: AttendanceConfig) -> Result<ConfigValues> {
+    check_config(&config).map_err(|mut errors| errors.remove(0))
+}
+
+/// Checks every field of the configuration, including how fields relate to
+/// each other, and returns all problems in form order. Empty when the
+/// configuration is valid.
+pub fn validate_config(config: &AttendanceConfig) -> Vec<PresenceError> {
+    check_config(config).err().unwrap_or_default()
+}
+
+fn check_config(
+    config: &AttendanceConfig,
+) -> std::result::Result<ConfigValues, Vec<PresenceError>> {
+    let mut errors = Vec::new();
+    let class_start = field_value(parse_time(&config.class_start, "class_start"), &mut errors);
+    let class_end = field_value(parse_time(&config.class_end, "class_end"), &mut errors);
+    if let (Some(start), Some(end)) = (class_start, class_end)
+        && end <= start
+    {
+        errors.push(PresenceError::config(
+            "class_end",
+            "Class end time must be after the start time.",
+        ));
+    }
+    let late_minutes = field_value(
+        parse_minutes(&config.late_minutes, "late_minutes", "Late minutes"),
+        &mut errors,
+    );
+    let absent_minutes = field_value(
+        parse_minutes(&config.absent_minutes, "absent_minutes", "Absent minutes"),
+        &mut errors,
+    );
+    if let (Some(late), Some(absent)) = (late_minutes, absent_minutes)
+        && absent < late
+    {
+        errors.push(PresenceError::config(
+            "absent_minutes",
+            "Absent minutes must not be smaller than late minutes.",
+        ));
+    }
+    let total_points = field_value(
+        parse_float(&config.total_points, "total_points", "Total points"),
+        &mut errors,
+    );
+    if total_points.is_some_and(|total| total < 0.0) {
+        errors.push(PresenceError::config(
+            "total_points",
+            "Total points must not be negative.",
+        ));
+    }
+    let late_penalty = field_value(
+        parse_float(&config.late_penalty, "late_penalty", "Late penalty"),
+        &mut errors,
+    );
+    if late_penalty.is_some_and(|penalty| !(0.0..=1.0).contains(&penalty)) {
+        errors.push(PresenceError::config(
+            "late_penalty",
+            "Late penalty must be between 0 and 1.",
+        ));
+    }
+    let min_attendance = field_value(
+        parse_float(
+            &config.min_attendance,
+            "min_attendance",
+            "Minimum attendance",
+        ),
+        &mut errors,
+    );
+    if min_attendance.is_some_and(|min| !(0.0..=100.0).contains(&min)) {
+        errors.push(PresenceError::config(
+            "min_attendance",
+            "Minimum attendance must be between 0 and 100 percent.",
+        ));
+    }
+
+    match (
+        class_start,
+        class_end,
+        late_minutes,
+        absent_minutes,
+        total_points,
+        late_penalty,
+        min_attendance,
+    ) {
+        (
+            Some(class_start),
+            Some(class_end),
+            Some(late_minutes),
+            Some(absent_minutes),
+            Some(total_points),
+            Some(late_penalty),
+            Some(min_attendance),
+        ) if errors.is_empty() => Ok(ConfigValues {
+            class_start,
+            class_end,
+            late_minutes,
+            absent_minutes,
+            total_points,
+            late_penalty,
+            min_attendance: min_attendance / 100.0,
+        }),
+        _ => Err(errors),
+    }
+}
+
+/// Keeps the value of a parsed field, or records why it failed.
+fn field_value<T>(result: Result<T>, errors: &mut Vec<PresenceError>) -> Option<T> {
+    result.map_err(|error| errors.push(error)).ok()
+}
+
+fn parse_time(input: &str, field: &'static str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(input.trim(), "%H:%M").map_err(|_| {
+        PresenceError::config(field, format!("Invalid time format: {input}. Use HH:MM."))
+    })
+}
+
+fn parse_minutes(input: &str, field: &'static str, label: &str) -> Result<i64> {
+    let minutes = input
+        .trim()
+        .parse::<i64>()
+        .map_err(|_| PresenceError::config(field, format!("{label} must be a number.")))?;
+    if minutes < 0 {
+        return Err(PresenceError::config(
+            field,
+            format!("{label} must not be negative."),
+        ));
+    }
+    Ok(minutes)
+}
+
+fn parse_float(input: &str, field: &'static str, label: &str) -> Result<f32> {
+    input
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| PresenceError::config(field, format!("{label} must be a number.")))
+}
+
+/// Parses one attendance export. Errors carry the offending line but no path;
+/// callers that know the file attach it with [`PresenceError::with_path`].
+pub fn parse_participants(data: &[u8], extension: &str) -> Result<Vec<Participant>> {
+    match extension {
+        "csv" => parse_csv_participants(data),
+        "xlsx" | "xls" => parse_excel_participants(data),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn parse_csv_participants(data: &[u8]) -> Result<Vec<Participant>> {
+    let (contents, delimiter) = decode_csv_text(data);
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    let mut header: Option<Vec<String>> = None;
+    let mut participants: HashMap<String, Participant> = HashMap::new();
+
+    for result in reader.records() {
+        let record = result.map_err(|source| PresenceError::Csv { path: None, source })?;
+        if header.is_none() && record.iter().any(|cell| cell == "Name") {
+            header = Some(record.iter().map(|cell| cell.trim().to_string()).collect());
+            continue;
+        }
+        if let Some(header) = &header {
+            let line = record.position().map(|position| position.line());
+            let participant = parse_participant_row(header, &record, line)?;
+            if let Some(participant) = participant {
+                let key = build_key(&participant);
+                participants
+                    .entry(key)
+                    .and_modify(|existing| {
+                        if participant.first_join < existing.first_join {
+                            *existing = participant.clone();
+                        }
+                    })
+                    .or_insert(participant);
+            }
+        }
+    }
+
+    Ok(participants.into_values().collect())
+}
+
+fn decode_csv_text(bytes: &[u8]) -> (String, u8) {
+    let (decoded, _encoding) = if bytes.starts_with(&[0xFF, 0xFE]) {
+        UTF_16LE.decode_with_bom_removal(bytes)
+    } else if bytes.starts_with(&[0xFE, 0xFF]) {
+        UTF_16BE.decode_with_bom_removal(bytes)
+    } else if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        UTF_8.decode_with_bom_removal(bytes)
+    } else {
+        match std::str::from_utf8(bytes) {
+            Ok(value) => return (value.to_string(), detect_delimiter(bytes)),
+            Err(_) => WINDOWS_1252.decode_without_bom_handling(bytes),
+        }
+    };
+    let delimiter = detect_delimiter(bytes);
+    (decoded.into_owned(), delimiter)
+}
+
+fn detect_delimiter(bytes: &[u8]) -> u8 {
+    let sample = String::from_utf8_lossy(bytes);
+    let line = sample
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default();
+    let comma = line.matches(',').count();
+    let tab = line.matches('\t').count();
+    let semicolon = line.matches(';').count();
+    if tab >= comma && tab >= semicolon {
+        b'\t'
+    } else if semicolon > comma {
+        b';'
+    } else {
+        b','
+    }
+}
+
+fn parse_excel_participants(data: &[u8]) -> Result<Vec<Participant>> {
+    let excel_error = |source| PresenceError::Excel { path: None, source };
+    let cursor = Cursor::new(data);
+    let mut workbook: Xlsx<_> = Xlsx::new(cursor).map_err(excel_error)?;
+    let sheet_name = workbook
+        .sheet_names()
+        .first()
+        .cloned()
+        .ok_or(PresenceError::EmptyWorkbook { path: None })?;
+    let range = workbook.worksheet_range(&sheet_name).map_err(excel_error)?;
+    let mut header: Option<Vec<String>> = None;
+    let mut participants: HashMap<String, Participant> = HashMap::new();
+
+    let first_row = range.start().map_or(0, |(row, _)| row as u64);
+    for (index, row) in range.rows().enumerate() {
+        let line = first_row + index as u64 + 1;
+        let record: Vec<String> = row.iter().map(cell_to_string).collect();
+        let record = StringRecord::from(record);
+        if header.is_none() && record.iter().any(|cell| cell == "Name") {
+            header = Some(record.iter().map(|cell| cell.trim().to_string()).collect());
+            continue;
+        }
+        if let Some(header) = &header {
+            let participant = parse_participant_row(header, &record, Some(line))?;
+            if let Some(participant) = participant {
+                let key = build_key(&participant);
+                participants
+                    .entry(key)
+                    .and_modify(|existing| {
+                        if participant.first_join < existing.first_join {
+                            *existing = participant.clone();
+                        }
+                    })
+                    .or_insert(participant);
+            }
+        }
+    }
+
+    Ok(participants.into_values().collect())
+}
+
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::String(value) => value.clone(),
+        Data::Float(value) => value.to_string(),
+        Data::Int(value) => value.to_string(),
+        Data::Bool(value) => value.to_string(),
+        Data::DateTime(value) => value.to_string(),
+        Data::DateTimeIso(value) => value.to_string(),
+        Data::DurationIso(value) => value.to_string(),
+        Data::Error(error) => format!("{error:?}"),
+        Data::Empty => String::new(),
+    }
+}
+
+fn parse_participant_row(
+    header: &[String],
+    record: &StringRecord,
+    line: Option<u64>,
+) -> Result<Option<Participant>> {
+    let name_index = header.iter().position(|cell| cell == "Name");
+    let join_index = header.iter().position(|cell| cell == "First Join");
+    let email_index = header.iter().position(|cell| cell == "Email");
+    let section_index = header.iter().position(|cell| cell == "Section");
+    if name_index.is_none() || join_index.is_none() {
+        return Ok(None);
+    }
+    let name = record
+        .get(name_index.unwrap())
+        .map(|value| value.trim())
+        .unwrap_or_default();
+    if name.is_empty() || name == "Name" {
+        return Ok(None);
+    }
+    let join_value = record
+        .get(join_index.unwrap())
+        .map(|value| value.trim())
+        .unwrap_or_default();
+    if join_value.is_empty() {
+        return Ok(None);
+    }
+    let email = email_index
+        .and_then(|index| record.get(index))
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default();
+    let section = section_index
+        .and_then(|index| record.get(index))
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default();
+    let first_join = parse_datetime(join_value).ok_or_else(|| PresenceError::InvalidValue {
+        path: None,
+        line,
+        field: "First Join".to_string(),
+        value: join_value.to_string(),
+    })?;
+    let (first, surname) = split_name(name);
+    let id = extract_id(&email);
+    Ok(Some(Participant {
+        name: first,
+        surname,
+        id,
+        email,
+        section,
+        first_join,
+    }))
+}
+
+fn parse_datetime(input: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(input, "%m/%d/%y, %I:%M:%S %p")
+        .or_else(|_| NaiveDateTime::parse_from_str(input, "%m/%d/%Y, %I:%M:%S %p"))
+        .ok()
+}
+
+pub fn split_name(input: &str) -> (String, String) {
+    let parts: Vec<&str> = input.split_whitespace().collect();
+    if parts.is_empty() {
+        return (String::new(), String::new());
+    }
+    if parts.len() == 1 {
+        return (parts[0].to_string(), String::new());
+    }
+    let name = parts[0].to_string();
+    let surname = parts[1..].join(" ");
+    (name, surname)
+}
+
+pub fn extract_id(email: &str) -> String {
+    email.split('@').next().unwrap_or_default().to_string()
+}
+
+fn build_key(participant: &Participant) -> String {
+    student_key(
+        &participant.id,
+        &participant.email,
+        &participant.name,
+        &participant.surname,
+    )
+}
+
+fn student_key(id: &str, email: &str, name: &str, surname: &str) -> String {
+    if !id.is_empty() {
+        id.to_string()
+    } else if !email.is_empty() {
+        email.to_string()
+    } else {
+        format!("{name} {surname}")
+    }
+}
+
+pub fn generate_report(
+    sessions: Vec<Vec<Participant>>,
+    config: AttendanceConfig,
+) -> Result<AttendanceReport> {
+    let config = parse_config(config)?;
+    let mut students: HashMap<String, StudentRecord> = HashMap::new();
+    let mut session_dates: Vec<NaiveDate> = Vec::new();
+    let mut sessions_processed = 0usize;
+
+    for participants in sessions {
+        if participants.is_empty() {
+            continue;
+        }
+
+        let session_date = participants
+            .first()
+            .map(|participant| participant.first_join.date())
+            .unwrap_or_else(|| NaiveDate::from_ymd_opt(1970, 1, 1).unwrap());
+        let class_start = NaiveDateTime::new(session_date, config.class_start);
+
+        let mut session_keys: HashSet<String> = HashSet::new();
+
+        for participant in participants {
+            let key = build_key(&participant);
+            let status = classify_attendance(&participant, class_start, &config);
+            session_keys.insert(key.clone());
+
+            let record = students.entry(key).or_insert_with(|| StudentRecord {
+                name: participant.name.clone(),
+                surname: participant.surname.clone(),
+                id: participant.id.clone(),
+                email: participant.email.clone(),
+                section: participant.section.clone(),
+                normal: 0,
+                late: 0,
+                absent: sessions_processed as u32,
+                score: 0.0,
+                history: session_dates
+                    .iter()
+                    .map(|date| SessionAttendance::missed(*date))
+                    .collect(),
+            });
+
+            apply_status(record, status);
+            record.history.push(SessionAttendance {
+                date: session_date,
+                status,
+                first_join: Some(participant.first_join),
+                minutes_late: (participant.first_join - class_start).num_minutes().max(0),
+                original: None,
+            });
+        }
+
+        for (key, record) in students.iter_mut() {
+            if !session_keys.contains(key) {
+                record.absent += 1;
+                record.history.push(SessionAttendance::missed(session_date));
+            }
+        }
+
+        session_dates.push(session_date);
+        sessions_processed += 1;
+    }
+
+    for record in students.values_mut() {
+        record.score = calculate_score(record, &config);
+    }
+
+    let mut students: Vec<StudentRecord> = students.into_values().collect();
+    students.sort_by(|a, b| a.surname.cmp(&b.surname).then(a.name.cmp(&b.name)));
+
+    Ok(AttendanceReport {
+        students,
+        sessions: sessions_processed,
+        total_points: config.total_points,
+        session_dates,
+        session_sources: Vec::new(),
+        config,
+    })
+}
+
+fn classify_attendance(
+    participant: &Participant,
+    class_start: NaiveDateTime,
+    config: &ConfigValues,
+) -> AttendanceStatus {
+    let delta = participant.first_join - class_start;
+    let minutes = delta.num_minutes().max(0);
+    if minutes <= config.late_minutes {
+        AttendanceStatus::Normal
+    } else if minutes <= config.absent_minutes {
+        AttendanceStatus::Late
+    } else {
+        AttendanceStatus::Absent
+    }
+}
+
+fn apply_status(record: &mut StudentRecord, status: AttendanceStatus) {
+    match status {
+        AttendanceStatus::Normal => record.normal += 1,
+        AttendanceStatus::Late => record.late += 1,
+        AttendanceStatus::Absent => record.absent += 1,
+    }
+}
+
+pub(crate) fn calculate_score(record: &StudentRecord, config: &ConfigValues) -> f32 {
+    let score = (record.normal as f32) + (record.late as f32 * config.late_penalty);
+    score.min(config.total_points).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::Profile;
+
+    #[test]
+    fn test_calculate_score_accumulation() {
+        let config = ConfigValues {
+            class_start: NaiveTime::from_hms_opt(13, 30, 0).unwrap(),
+            class_end: NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
+            late_minutes: 10,
+            absent_minutes: 30,
+            total_points: 10.0,
+            late_penalty: 0.5,
+            min_attendance: 0.7,
+        };
+
+        let record = StudentRecord {
+            name: "Test".to_string(),
+            surname: "User".to_string(),
+            id: "123".to_string(),
+            email: "test@example.com".to_string(),
+            section: String::new(),
+            normal: 3,
+            late: 4,
+            absent: 2,
+            score: 0.0,
+            history: Vec::new(),
+        };
+
+        // User example: 4 late * 0.5 + 3 normal * 1.0 = 2.0 + 3.0 = 5.0
+        let score = calculate_score(&record, &config);
+        assert_eq!(score, 5.0);
+    }
+
+    #[test]
+    fn test_split_name() {
+        assert_eq!(
+            split_name("John Doe"),
+            ("John".to_string(), "Doe".to_string())
+        );
+        assert_eq!(split_name("John"), ("John".to_string(), "".to_string()));
+        assert_eq!(split_name(""), ("".to_string(), "".to_string()));
+        assert_eq!(
+            split_name("John Von Neumann"),
+            ("John".to_string(), "Von Neumann".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_id() {
+        assert_eq!(extract_id("student@univ.edu"), "student");
+        assert_eq!(extract_id("user.name+tag@gmail.com"), "user.name+tag");
+        assert_eq!(extract_id("plainstring"), "plainstring");
+        assert_eq!(extract_id(""), "");
+    }
+
+    #[test]
+    fn test_parse_config_valid() {
+        let config = AttendanceConfig {
+            class_start: "09:00".to_string(),
+            class_end: "10:30".to_string(),
+            late_minutes: "15".to_string(),
+            absent_minutes: "60".to_string(),
+            total_points: "100".to_string(),
+            late_penalty: "0.8".to_string(),
+            min_attendance: "70".to_string(),
+        };
+
+        let result = parse_config(config).unwrap();
+        assert_eq!(
+            result.class_start,
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+        );
+        assert_eq!(result.late_minutes, 15);
+        assert_eq!(result.absent_minutes, 60);
+        assert_eq!(result.total_points, 100.0);
+        assert_eq!(result.late_penalty, 0.8);
+        assert_eq!(result.min_attendance, 0.7);
+    }
+
+    #[test]
+    fn test_parse_config_invalid() {
+        let config = AttendanceConfig {
+            class_start: "invalid".to_string(),
+            class_end: "10:30".to_string(),
+            late_minutes: "15".to_string(),
+            absent_minutes: "60".to_string(),
+            total_points: "100".to_string(),
+            late_penalty: "0.8".to_string(),
+            min_attendance: "70".to_string(),
+        };
+        assert!(matches!(
+            parse_config(config.clone()),
+            Err(PresenceError::Config {
+                field: "class_start",
+                ..
+            })
+        ));
+
+        // Test end time before start time
+        let config = AttendanceConfig {
+            class_start: "10:30".to_string(),
+            class_end: "09:00".to_string(),
+            late_minutes: "15".to_string(),
+            absent_minutes: "60".to_string(),
+            total_points: "100".to_string(),
+            late_penalty: "0.8".to_string(),
+            min_attendance: "70".to_string(),
+        };
+        assert!(matches!(
+            parse_config(config),
+            Err(PresenceError::Config {
+                field: "class_end",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_min_attendance_range() {
+        let valid = Profile::default().to_config();
+        let rejected_field = |config: AttendanceConfig| match parse_config(config) {
+            Err(PresenceError::Config { field, .. }) => Some(field),
+            _ => None,
+        };
+
+        // Minimum attendance is a percentage
+        let config = AttendanceConfig {
+            min_attendance: "120".to_string(),
+            ..valid.clone()
+        };
+        assert_eq!(rejected_field(config), Some("min_attendance"));
+        let config = AttendanceConfig {
+            min_attendance: "-1".to_string(),
+            ..valid.clone()
+        };
+        assert_eq!(rejected_field(config), Some("min_attendance"));
+
+        // Students can only turn absent after turning late
+        let config = AttendanceConfig {
+            late_minutes: "30".to_string(),
+            absent_minutes: "20".to_string(),
+            ..valid.clone()
+        };
+        assert_eq!(rejected_field(config), Some("absent_minutes"));
+        let config = AttendanceConfig {
+            late_minutes: "30".to_string(),
+            absent_minutes: "30".to_string(),
+            ..valid.clone()
+        };
+        assert!(parse_config(config).is_ok());
+
+        // A late session earns between nothing and a full session
+        let config = AttendanceConfig {
+            late_penalty: "1.5".to_string(),
+            ..valid.clone()
+        };
+        assert_eq!(rejected_field(config), Some("late_penalty"));
+        let config = AttendanceConfig {
+            late_penalty: "-0.1".to_string(),
+            ..valid.clone()
+        };
+        assert_eq!(rejected_field(config), Some("late_penalty"));
+        let config = AttendanceConfig {
+            late_penalty: "1".to_string(),
+            ..valid.clone()
+        };
+        assert!(parse_config(config).is_ok());
+
+        let config = AttendanceConfig {
+            total_points: "-10".to_string(),
+            ..valid.clone()
+        };
+        assert_eq!(rejected_field(config), Some("total_points"));
+        let config = AttendanceConfig {
+            total_points: "0".to_string(),
+            min_attendance: "0".to_string(),
+            ..valid
+        };
+        assert!(parse_config(config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_config_lists_every_problem() {
+        let valid = Profile::default().to_config();
+        assert!(validate_config(&valid).is_empty());
+
+        let config = AttendanceConfig {
+            class_start: "9".to_string(),
+            late_minutes: "-5".to_string(),
+            total_points: "-10".to_string(),
+            late_penalty: "NaN".to_string(),
+            ..valid.clone()
+        };
+        let fields: Vec<&str> = validate_config(&config)
+            .iter()
+            .filter_map(|error| match error {
+                PresenceError::Config { field, .. } => Some(*field),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "class_start",
+                "late_minutes",
+                "total_points",
+                "late_penalty"
+            ]
+        );
+        assert!(matches!(
+            parse_config(config),
+            Err(PresenceError::Config {
+                field: "class_start",
+                ..
+            })
+        ));
+
+        // Absent threshold below the late one
+        let config = AttendanceConfig {
+            late_minutes: "30".to_string(),
+            absent_minutes: "20".to_string(),
+            late_penalty: "1.5".to_string(),
+            ..valid
+        };
+        let errors = validate_config(&config);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].to_string(),
+            "Absent minutes must not be smaller than late minutes."
+        );
+    }
+
+    #[test]
+    fn test_parse_participants_reports_line() {
+        let csv_data = "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"\nJane Smith,jane@example.com,yesterday";
+        let error = parse_participants(csv_data.as_bytes(), "csv").unwrap_err();
+        match &error {
+            PresenceError::InvalidValue {
+                line, field, value, ..
+            } => {
+                assert_eq!(*line, Some(3));
+                assert_eq!(field, "First Join");
+                assert_eq!(value, "yesterday");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_participants() {
+        // Mock CSV data: Name, User Email, Join Time - Date needs quotes because it contains a comma
+        let csv_data = "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"\nJane Smith,jane@example.com,\"10/25/23, 1:40:00 PM\"";
+        let participants =
+            parse_participants(csv_data.as_bytes(), "csv").expect("Failed to parse CSV");
+
+        assert_eq!(participants.len(), 2);
+
+        // Sorting isn't guaranteed by hashmap, so we find by ID
+        let john = participants
+            .iter()
+            .find(|p| p.email == "john@example.com")
+            .unwrap();
+        assert_eq!(john.name, "John");
+        assert_eq!(john.surname, "Doe");
+        // Time parsing check: 1:30 PM = 13:30
+        assert_eq!(
+            john.first_join.time(),
+            NaiveTime::from_hms_opt(13, 30, 0).unwrap()
+        );
+
+        let jane = participants
+            .iter()
+            .find(|p| p.email == "jane@example.com")
+            .unwrap();
+        assert_eq!(jane.name, "Jane");
+        assert_eq!(jane.surname, "Smith");
+        assert_eq!(
+            jane.first_join.time(),
+            NaiveTime::from_hms_opt(13, 40, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate_report_integration() {
+        let config = AttendanceConfig {
+            class_start: "13:30".to_string(),
+            class_end: "15:00".to_string(),
+            late_minutes: "10".to_string(),
+            absent_minutes: "30".to_string(),
+            total_points: "10.0".to_string(),
+            late_penalty: "0.5".to_string(),
+            min_attendance: "70".to_string(),
+        };
+
+        // Session 1: John is Normal (13:30), Jane is Late (13:41), Bob is Absent (not present)
+        let session1_csv = "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"\nJane Smith,jane@example.com,\"10/25/23, 1:41:00 PM\"";
+
+        // Session 2: John is Absent (not present), Jane is Normal (13:35), Bob is Normal (13:30)
+        let session2_csv = "Name,Email,First Join\nJane Smith,jane@example.com,\"10/27/23, 1:35:00 PM\"\nBob Builder,bob@example.com,\"10/27/23, 1:30:00 PM\"";
+
+        let session1 = parse_participants(session1_csv.as_bytes(), "csv").unwrap();
+        let session2 = parse_participants(session2_csv.as_bytes(), "csv").unwrap();
+
+        let report =
+            generate_report(vec![session1, session2], config).expect("Failed to generate report");
+
+        assert_eq!(report.sessions, 2);
+        assert_eq!(report.students.len(), 3);
+
+        // Verify John: 1 Normal, 0 Late, 1 Absent. Score = 1.0
+        let john = report
+            .students
+            .iter()
+            .find(|s| s.email == "john@example.com")
+            .unwrap();
+        assert_eq!(john.normal, 1);
+        assert_eq!(john.late, 0);
+        assert_eq!(john.absent, 1);
+        assert_eq!(john.score, 1.0);
+
+        // Verify Jane: 1 Normal, 1 Late, 0 Absent. Score = 1.0 + 0.5 = 1.5
+        let jane = report
+            .students
+            .iter()
+            .find(|s| s.email == "jane@example.com")
+            .unwrap();
+        assert_eq!(jane.normal, 1);
+        assert_eq!(jane.late, 1);
+        assert_eq!(jane.absent, 0);
+        assert_eq!(jane.score, 1.5);
+
+        // Verify Bob: 1 Normal, 0 Late, 1 Absent (Session 1). Score = 1.0
+        let bob = report
+            .students
+            .iter()
+            .find(|s| s.email == "bob@example.com")
+            .unwrap();
+        assert_eq!(bob.normal, 1);
+        assert_eq!(bob.late, 0);
+        assert_eq!(bob.absent, 1);
+        assert_eq!(bob.score, 1.0);
+    }
+
+    /// John attends the first of two sessions on time, Jane is late to the
+    /// first and on time to the second, Bob only joins the second.
+    fn sample_report() -> AttendanceReport {
+        let config = Profile::default().to_config();
+        let session1_csv = "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"\nJane Smith,jane@example.com,\"10/25/23, 1:41:00 PM\"";
+        let session2_csv = "Name,Email,First Join\nJane Smith,jane@example.com,\"10/27/23, 1:35:00 PM\"\nBob Builder,bob@example.com,\"10/27/23, 1:30:00 PM\"";
+        let session1 = parse_participants(session1_csv.as_bytes(), "csv").unwrap();
+        let session2 = parse_participants(session2_csv.as_bytes(), "csv").unwrap();
+        generate_report(vec![session1, session2], config).unwrap()
+    }
+
+    fn sample_student<'a>(report: &'a AttendanceReport, email: &str) -> &'a StudentRecord {
+        report.students.iter().find(|s| s.email == email).unwrap()
+    }
+
+    #[test]
+    fn test_report_history_and_summary() {
+        let report = sample_report();
+        let jane = sample_student(&report, "jane@example.com");
+        let bob = sample_student(&report, "bob@example.com");
+
+        // History is padded for sessions before a student first appeared
+        let statuses: Vec<AttendanceStatus> = bob.history.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            vec![AttendanceStatus::Absent, AttendanceStatus::Normal]
+        );
+        assert!(bob.history[0].first_join.is_none());
+        assert_eq!(jane.history[0].status, AttendanceStatus::Late);
+        assert_eq!(jane.history[0].minutes_late, 11);
+        assert_eq!(
+            report.date_range(),
+            Some((
+                NaiveDate::from_ymd_opt(2023, 10, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 10, 27).unwrap()
+            ))
+        );
+
+        let summary = summarize(&report);
+        assert_eq!(summary.students, 3);
+        assert_eq!(summary.mean_rate, 2.0 / 3.0);
+        assert_eq!(summary.median_rate, 0.5);
+        assert_eq!(summary.max_rate, 1.0);
+        assert_eq!(summary.rate_histogram[5], 2);
+        assert_eq!(summary.rate_histogram[9], 1);
+    }
+
+    #[test]
+    fn test_eligibility_uses_min_attendance() {
+        let report = sample_report();
+        // Jane attended both sessions, John one of two, below the 70% minimum
+        assert!(report.is_eligible(sample_student(&report, "jane@example.com")));
+        assert!(!report.is_eligible(sample_student(&report, "john@example.com")));
+        assert_eq!(summarize(&report).eligible, 1);
+    }
+
+    #[test]
+    fn test_at_risk_and_guest() {
+        let report = sample_report();
+        let jane = sample_student(&report, "jane@example.com");
+        // One more absence would leave Jane at 2/3, below the 70% minimum
+        assert!(report.is_at_risk(jane));
+        // John is already ineligible, so no longer at risk
+        assert!(!report.is_at_risk(sample_student(&report, "john@example.com")));
+        assert!(!jane.is_guest());
+    }
+
+    #[test]
+    fn test_summary_dashboard_series() {
+        let summary = summarize(&sample_report());
+        assert_eq!(summary.at_risk, 1);
+        assert_eq!(summary.session_rates, vec![2.0 / 3.0, 2.0 / 3.0]);
+        assert_eq!(summary.score_histogram.iter().sum::<usize>(), 3);
+        // Four joins, Jane's 11 minutes late in the third bucket
+        assert_eq!(summary.lateness_histogram.iter().sum::<usize>(), 4);
+        assert_eq!(summary.lateness_histogram[2], 1);
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        let comma_csv = b"Name,Email,First Join\nJohn,j@e.com,Time";
+        assert_eq!(detect_delimiter(comma_csv), b',');
+
+        let tab_csv = b"Name\tEmail\tFirst Join\nJohn\tj@e.com\tTime";
+        assert_eq!(detect_delimiter(tab_csv), b'\t');
+
+        let semi_csv = b"Name;Email;First Join\nJohn;j@e.com;Time";
+        assert_eq!(detect_delimiter(semi_csv), b';');
+    }
+}
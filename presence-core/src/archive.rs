@@ -0,0 +1,193 @@
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+use crate::analysis::{self, Participant};
+use crate::error::{PresenceError, Result};
+use crate::loader::is_attendance_file;
+
+/// Largest export read from an archive. Real exports stay far below this;
+/// the cap keeps a corrupt or crafted archive from exhausting memory.
+const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Whether a path is a ZIP archive, e.g. a bulk download of meeting reports.
+pub fn is_archive(path: &Path) -> bool {
+    matches!(path.extension().and_then(|ext| ext.to_str()), Some("zip"))
+}
+
+/// Whether an archive entry is an export. Folders and the resource-fork
+/// copies macOS adds under `__MACOSX/` are skipped.
+fn is_archived_export(name: &str) -> bool {
+    let path = Path::new(name);
+    !name.ends_with('/')
+        && !name.split('/').any(|part| part == "__MACOSX")
+        && !path
+            .file_name()
+            .and_then(|file| file.to_str())
+            .is_some_and(|file| file.starts_with("._"))
+        && is_attendance_file(path)
+}
+
+fn archive_error(path: &Path, source: ZipError) -> PresenceError {
+    PresenceError::Archive {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+enum EntryError {
+    TooLarge,
+    Io(std::io::Error),
+}
+
+/// Reads an archive entry of `size` bytes, as declared by the archive, into
+/// memory. The declared size can be wrong, so at most `limit` bytes are read
+/// whatever it says.
+fn read_entry(entry: impl Read, size: u64, limit: u64) -> Result<Vec<u8>, EntryError> {
+    if size > limit {
+        return Err(EntryError::TooLarge);
+    }
+    let mut contents = Vec::new();
+    entry
+        .take(limit + 1)
+        .read_to_end(&mut contents)
+        .map_err(EntryError::Io)?;
+    if contents.len() as u64 > limit {
+        return Err(EntryError::TooLarge);
+    }
+    Ok(contents)
+}
+
+/// Parses every export inside a ZIP archive read into memory, one session per
+/// entry, ordered by entry name. `path` is the archive on disk; sessions and
+/// parse errors name the entry as `archive.zip/entry.csv`.
+pub fn parse_archive(path: &Path, data: &[u8]) -> Result<Vec<(PathBuf, Vec<Participant>)>> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(data)).map_err(|error| archive_error(path, error))?;
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| is_archived_export(name))
+        .map(str::to_string)
+        .collect();
+    names.sort();
+
+    names
+        .iter()
+        .map(|name| {
+            let mut entry = archive
+                .by_name(name)
+                .map_err(|error| archive_error(path, error))?;
+            let source = path.join(name);
+            let size = entry.size();
+            let contents =
+                read_entry(&mut entry, size, MAX_ENTRY_SIZE).map_err(|error| match error {
+                    EntryError::TooLarge => PresenceError::ArchiveEntryTooLarge {
+                        path: source.clone(),
+                        limit: MAX_ENTRY_SIZE,
+                    },
+                    EntryError::Io(error) => archive_error(path, error.into()),
+                })?;
+            let extension = Path::new(name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("");
+            let participants = analysis::parse_participants(&contents, extension)
+                .map_err(|error| error.with_path(&source))?;
+            Ok((source, participants))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    fn zip_of(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_parse_archive_and_load_from_folder() {
+        let session = |name: &str, time: &str| {
+            format!("Name,Email,First Join\n{name},{name}@example.com,\"10/25/23, {time}\"")
+        };
+        let data = zip_of(&[
+            ("week-02/session.csv", &session("Jane", "1:45:00 PM")),
+            ("week-01/session.csv", &session("John", "1:30:00 PM")),
+            ("__MACOSX/week-01/._session.csv", "junk"),
+            ("readme.txt", "not an export"),
+        ]);
+
+        let sessions = parse_archive(Path::new("bulk.zip"), &data).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].0, Path::new("bulk.zip/week-01/session.csv"));
+        assert_eq!(sessions[0].1[0].name, "John");
+
+        // Archives next to loose exports are picked up by the loader
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bulk.zip"), &data).unwrap();
+        std::fs::write(
+            dir.path().join("week-03.csv"),
+            session("John", "1:50:00 PM"),
+        )
+        .unwrap();
+        let report = crate::load_attendance(
+            dir.path().to_path_buf(),
+            crate::profile::Profile::default().to_config(),
+        )
+        .unwrap();
+        assert_eq!(report.sessions, 3);
+        assert_eq!(
+            report.session_sources[2],
+            dir.path().join("week-03.csv"),
+            "sources stay aligned with sessions"
+        );
+
+        let broken = zip_of(&[("week-01.csv", "Name,Email,First Join\nJohn,j@x.com,soon")]);
+        let error = parse_archive(Path::new("bulk.zip"), &broken).unwrap_err();
+        assert_eq!(error.path(), Some(Path::new("bulk.zip/week-01.csv")));
+        assert!(matches!(
+            parse_archive(Path::new("bulk.zip"), b"not a zip"),
+            Err(PresenceError::Archive { .. })
+        ));
+    }
+    #[test]
+    fn test_oversized_entries_are_rejected() {
+        let contents = [b'x'; 32];
+        assert_eq!(
+            read_entry(&contents[..], 32, 32).ok(),
+            Some(contents.to_vec())
+        );
+        // A declared size over the limit is refused before reading
+        assert!(matches!(
+            read_entry(&contents[..], 33, 32),
+            Err(EntryError::TooLarge)
+        ));
+        // So is an entry that turns out larger than it claimed
+        assert!(matches!(
+            read_entry(&contents[..], 8, 16),
+            Err(EntryError::TooLarge)
+        ));
+
+        let error = PresenceError::ArchiveEntryTooLarge {
+            path: PathBuf::from("bulk.zip/week-01.csv"),
+            limit: MAX_ENTRY_SIZE,
+        };
+        assert_eq!(error.path(), Some(Path::new("bulk.zip/week-01.csv")));
+        assert_eq!(
+            error.to_string(),
+            "bulk.zip/week-01.csv is larger than 64 MB and was not read."
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::SystemTime;
+
+use crate::analysis::{self, Participant};
+use crate::archive;
+use crate::error::{PresenceError, Result};
+
+/// Parsed attendance exports kept between analyses.
+///
+/// An entry is reused as long as the file's size and modification time are
+/// unchanged. When they differ the file is read again, and it is only
+/// re-parsed if its content hash changed too, so touching a file is cheap.
+/// Since parsing is the expensive step, changing only the attendance settings
+/// re-runs classification and scoring on cached sessions. A ZIP archive is
+/// cached as one entry holding all of its sessions.
+#[derive(Debug, Clone, Default)]
+pub struct SessionCache {
+    entries: HashMap<PathBuf, CachedSession>,
+    parsed: usize,
+    reused: usize,
+}
+
+#[derive(Debug, Clone)]
+struct CachedSession {
+    size: u64,
+    modified: Option<SystemTime>,
+    hash: u64,
+    sessions: Vec<(PathBuf, Vec<Participant>)>,
+}
+
+/// A file finished loading, reported by [`SessionCache::sessions_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadProgress {
+    /// Files finished so far, including this one.
+    pub done: usize,
+    pub total: usize,
+    pub file: PathBuf,
+}
+
+/// Stops a running load from another thread. Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Outcome of loading one file; `parsed` is false when the cached sessions
+/// were still valid.
+struct Loaded {
+    entry: CachedSession,
+    parsed: bool,
+}
+
+impl SessionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the source and participants of each session, in file order,
+    /// parsing only files that are new or changed. An archive contributes one
+    /// session per export inside it, with `archive.zip/entry.csv` as source.
+    /// Entries for files not in `files` are dropped.
+    pub fn sessions(&mut self, files: &[PathBuf]) -> Result<Vec<(PathBuf, Vec<Participant>)>> {
+        self.sessions_with(files, &CancelToken::new(), |_| {})
+    }
+
+    /// Like [`SessionCache::sessions`], but loads files on a pool of worker
+    /// threads, calls `on_progress` on the calling thread as each file
+    /// finishes and stops early with [`PresenceError::Cancelled`] once
+    /// `cancel` is set. Files loaded before a cancellation or error stay
+    /// cached.
+    pub fn sessions_with(
+        &mut self,
+        files: &[PathBuf],
+        cancel: &CancelToken,
+        mut on_progress: impl FnMut(&LoadProgress),
+    ) -> Result<Vec<(PathBuf, Vec<Participant>)>> {
+        self.parsed = 0;
+        self.reused = 0;
+        let workers = std::thread::available_parallelism()
+            .map_or(1, usize::from)
+            .min(files.len());
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let mut results: Vec<Option<Result<Loaded>>> = files.iter().map(|_| None).collect();
+
+        std::thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            for _ in 0..workers {
+                let sender = sender.clone();
+                let (entries, next, failed) = (&self.entries, &next, &failed);
+                scope.spawn(move || {
+                    while !cancel.is_cancelled() && !failed.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = files.get(index) else { break };
+                        let result = load_file(path, entries.get(path));
+                        failed.fetch_or(result.is_err(), Ordering::Relaxed);
+                        if sender.send((index, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+            for (done, (index, result)) in receiver.into_iter().enumerate() {
+                on_progress(&LoadProgress {
+                    done: done + 1,
+                    total: files.len(),
+                    file: files[index].clone(),
+                });
+                results[index] = Some(result);
+            }
+        });
+
+        let mut first_error = None;
+        for (path, result) in files.iter().zip(&mut results) {
+            match result.take() {
+                Some(Ok(loaded)) => {
+                    if loaded.parsed {
+                        self.parsed += 1;
+                    } else {
+                        self.reused += 1;
+                    }
+                    self.entries.insert(path.clone(), loaded.entry);
+                }
+                Some(Err(error)) => {
+                    first_error.get_or_insert(error);
+                }
+                None => {}
+            }
+        }
+        if cancel.is_cancelled() {
+            return Err(PresenceError::Cancelled);
+        }
+        if let Some(error) = first_error {
+            return Err(error);
+        }
+
+        self.entries.retain(|path, _| files.contains(path));
+        Ok(files
+            .iter()
+            .flat_map(|path| self.entries[path].sessions.clone())
+            .collect())
+    }
+
+    /// Files parsed during the last call to [`SessionCache::sessions`].
+    pub fn parsed(&self) -> usize {
+        self.parsed
+    }
+
+    /// Files served from the cache during the last call to [`SessionCache::sessions`].
+    pub fn reused(&self) -> usize {
+        self.reused
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Loads one file, reusing `cached` when the file is unchanged.
+fn load_file(path: &Path, cached: Option<&CachedSession>) -> Result<Loaded> {
+    let metadata = std::fs::metadata(path).map_err(|error| PresenceError::io(path, error))?;
+    let size = metadata.len();
+    let modified = metadata.modified().ok();
+    if let Some(entry) = cached
+        && entry.size == size
+        && entry.modified.is_some()
+        && entry.modified == modified
+    {
+        return Ok(Loaded {
+            entry: entry.clone(),
+            parsed: false,
+        });
+    }
+
+    let data = std::fs::read(path).map_err(|error| PresenceError::io(path, error))?;
+    let hash = content_hash(&data);
+    if let Some(entry) = cached
+        && entry.hash == hash
+    {
+        return Ok(Loaded {
+            entry: CachedSession {
+                size,
+                modified,
+                ..entry.clone()
+            },
+            parsed: false,
+        });
+    }
+
+    let sessions = if archive::is_archive(path) {
+        archive::parse_archive(path, &data)?
+    } else {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        vec![(
+            path.to_path_buf(),
+            analysis::parse_participants(&data, extension)
+                .map_err(|error| error.with_path(path))?,
+        )]
+    };
+    Ok(Loaded {
+        entry: CachedSession {
+            size,
+            modified,
+            hash,
+            sessions,
+        },
+        parsed: true,
+    })
+}
+
+fn content_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION1: &str =
+        "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"";
+    const SESSION2: &str =
+        "Name,Email,First Join\nJane Smith,jane@example.com,\"10/27/23, 1:45:00 PM\"";
+
+    #[test]
+    fn test_cache_parses_only_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("session1.csv");
+        let second = dir.path().join("session2.csv");
+        std::fs::write(&first, SESSION1).unwrap();
+        std::fs::write(&second, SESSION2).unwrap();
+        let files = vec![first.clone(), second.clone()];
+
+        let mut cache = SessionCache::new();
+        assert_eq!(cache.sessions(&files).unwrap().len(), 2);
+        assert_eq!((cache.parsed(), cache.reused()), (2, 0));
+
+        cache.sessions(&files).unwrap();
+        assert_eq!((cache.parsed(), cache.reused()), (0, 2));
+
+        // Same content rewritten: hashed again but not re-parsed
+        std::fs::write(&second, SESSION2).unwrap();
+        cache.sessions(&files).unwrap();
+        assert_eq!(cache.parsed(), 0);
+
+        std::fs::write(
+            &second,
+            format!("{SESSION2}\nBob Builder,bob@example.com,\"10/27/23, 1:30:00 PM\""),
+        )
+        .unwrap();
+        let sessions = cache.sessions(&files).unwrap();
+        assert_eq!((cache.parsed(), cache.reused()), (1, 1));
+        assert_eq!(sessions[1].1.len(), 2);
+
+        cache.sessions(&files[..1]).unwrap();
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_sessions_with_reports_progress_and_cancels() {
+        let dir = tempfile::tempdir().unwrap();
+        let files: Vec<PathBuf> = (1..=3)
+            .map(|week| dir.path().join(format!("week-{week:02}.csv")))
+            .collect();
+        for file in &files {
+            std::fs::write(file, SESSION1).unwrap();
+        }
+
+        let mut cache = SessionCache::new();
+        let mut progress = Vec::new();
+        let sessions = cache
+            .sessions_with(&files, &CancelToken::new(), |update| {
+                progress.push((update.done, update.total));
+            })
+            .unwrap();
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        assert!(matches!(
+            SessionCache::new().sessions_with(&files, &cancel, |_| {}),
+            Err(PresenceError::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,1000 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::analysis::{
+    AttendanceReport, AttendanceStatus, ClassSummary, ExportOptions, LATENESS_BUCKET_MINUTES,
+    StudentRecord, summarize,
+};
+use crate::error::{PresenceError, Result};
+
+/// Semantic colour of a chart element. Each renderer maps tones to its own
+/// palette: neon on dark in the GUI, print colours on white in exported files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Normal,
+    Late,
+    Absent,
+    Accent,
+}
+
+impl From<AttendanceStatus> for Tone {
+    fn from(status: AttendanceStatus) -> Self {
+        match status {
+            AttendanceStatus::Normal => Tone::Normal,
+            AttendanceStatus::Late => Tone::Late,
+            AttendanceStatus::Absent => Tone::Absent,
+        }
+    }
+}
+
+/// One slice, bar or point of a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mark {
+    pub label: String,
+    pub value: f32,
+    pub tone: Tone,
+    /// Drawn faded, e.g. a session the student did not join.
+    pub faint: bool,
+    /// Outlined, e.g. a session whose status was overridden.
+    pub marked: bool,
+}
+
+impl Mark {
+    fn new(label: impl Into<String>, value: f32, tone: Tone) -> Self {
+        Self {
+            label: label.into(),
+            value,
+            tone,
+            faint: false,
+            marked: false,
+        }
+    }
+}
+
+/// Dashed horizontal reference line, e.g. a threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guide {
+    pub value: f32,
+    pub tone: Tone,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Plot {
+    /// Slices proportional to their values.
+    Pie(Vec<Mark>),
+    /// Vertical bars on an axis from 0 to `max`.
+    Bars {
+        marks: Vec<Mark>,
+        max: f32,
+        guides: Vec<Guide>,
+        /// Print each bar's value above it.
+        show_values: bool,
+    },
+    /// Rates between 0 and 1 joined by a line.
+    Line {
+        marks: Vec<Mark>,
+        guides: Vec<Guide>,
+    },
+}
+
+/// Renderer-independent description of a chart. The GUI draws it on a canvas
+/// and [`to_svg`]/[`to_png`] draw it into files, so both show the same data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    pub title: String,
+    pub caption: String,
+    pub plot: Plot,
+}
+
+/// Share of a student's sessions per status.
+pub fn student_pie(student: &StudentRecord) -> Chart {
+    Chart {
+        title: format!("Attendance of {} {}", student.name, student.surname),
+        caption: format!(
+            "{} sessions, {:.1}% attended",
+            student.normal + student.late + student.absent,
+            student.attendance_rate() * 100.0
+        ),
+        plot: Plot::Pie(vec![
+            Mark::new("Normal", student.normal as f32, Tone::Normal),
+            Mark::new("Late", student.late as f32, Tone::Late),
+            Mark::new("Absent", student.absent as f32, Tone::Absent),
+        ]),
+    }
+}
+
+/// A student's sessions in order, coloured by status, with the minutes late
+/// as bar height. Missed sessions are faint full-height bars.
+pub fn student_timeline(report: &AttendanceReport, student: &StudentRecord) -> Chart {
+    let max = student
+        .history
+        .iter()
+        .map(|session| session.minutes_late)
+        .chain([report.config.absent_minutes, 1])
+        .max()
+        .unwrap_or(1) as f32;
+    let marks = student
+        .history
+        .iter()
+        .map(|session| Mark {
+            label: session.date.format("%Y-%m-%d").to_string(),
+            value: match session.first_join {
+                Some(_) => session.minutes_late as f32,
+                None => max,
+            },
+            tone: session.status.into(),
+            faint: session.first_join.is_none(),
+            marked: session.original.is_some(),
+        })
+        .collect();
+    Chart {
+        title: format!("Sessions of {} {}", student.name, student.surname),
+        caption: "Minutes late per session; dashed lines mark the late and absent thresholds."
+            .to_string(),
+        plot: Plot::Bars {
+            marks,
+            max,
+            guides: vec![
+                Guide {
+                    value: report.config.late_minutes as f32,
+                    tone: Tone::Late,
+                },
+                Guide {
+                    value: report.config.absent_minutes as f32,
+                    tone: Tone::Absent,
+                },
+            ],
+            show_values: false,
+        },
+    }
+}
+
+/// Attendance rate of every session over the term.
+pub fn session_trend(report: &AttendanceReport, summary: &ClassSummary) -> Chart {
+    let minimum = report.config.min_attendance;
+    let marks = report
+        .session_dates
+        .iter()
+        .zip(&summary.session_rates)
+        .map(|(date, rate)| {
+            let tone = if *rate < minimum {
+                Tone::Absent
+            } else {
+                Tone::Normal
+            };
+            Mark::new(date.format("%Y-%m-%d").to_string(), *rate, tone)
+        })
+        .collect();
+    Chart {
+        title: "Attendance by session".to_string(),
+        caption: format!(
+            "Share of students present (on time or late); the dashed line is the {:.1}% minimum.",
+            minimum * 100.0
+        ),
+        plot: Plot::Line {
+            marks,
+            guides: vec![Guide {
+                value: minimum,
+                tone: Tone::Absent,
+            }],
+        },
+    }
+}
+
+/// Students per tenth of the total points.
+pub fn score_histogram(report: &AttendanceReport, summary: &ClassSummary) -> Chart {
+    let width = report.total_points / summary.score_histogram.len() as f32;
+    histogram(
+        "Score distribution",
+        Tone::Accent,
+        format!(
+            "Students per tenth of the {} points.",
+            number_label(report.total_points)
+        ),
+        summary
+            .score_histogram
+            .iter()
+            .enumerate()
+            .map(|(index, count)| (number_label(index as f32 * width), *count)),
+    )
+}
+
+/// Joins per bucket of minutes after class start, over every session.
+pub fn lateness_histogram(summary: &ClassSummary) -> Chart {
+    let last = summary.lateness_histogram.len() - 1;
+    histogram(
+        "Lateness distribution",
+        Tone::Late,
+        format!("Joins per {LATENESS_BUCKET_MINUTES} minutes after class start, every session."),
+        summary
+            .lateness_histogram
+            .iter()
+            .enumerate()
+            .map(|(index, count)| {
+                let minutes = index as i64 * LATENESS_BUCKET_MINUTES;
+                let label = if index == last {
+                    format!("{minutes}+")
+                } else {
+                    minutes.to_string()
+                };
+                (label, *count)
+            }),
+    )
+}
+
+fn histogram(
+    title: &str,
+    tone: Tone,
+    caption: String,
+    buckets: impl Iterator<Item = (String, usize)>,
+) -> Chart {
+    let marks: Vec<Mark> = buckets
+        .map(|(label, count)| Mark::new(label, count as f32, tone))
+        .collect();
+    let max = marks.iter().map(|mark| mark.value).fold(0.0, f32::max);
+    Chart {
+        title: title.to_string(),
+        caption,
+        plot: Plot::Bars {
+            marks,
+            max,
+            guides: Vec::new(),
+            show_values: true,
+        },
+    }
+}
+
+/// Short number for axis labels: `2`, `2.5`.
+pub fn number_label(value: f32) -> String {
+    let label = format!("{value:.1}");
+    match label.strip_suffix(".0") {
+        Some(whole) => whole.to_string(),
+        None => label,
+    }
+}
+
+/// The charts that can be exported, by command-line key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartType {
+    Pie,
+    Timeline,
+    Trend,
+    Scores,
+    Lateness,
+}
+
+impl ChartType {
+    pub const ALL: [ChartType; 5] = [
+        ChartType::Pie,
+        ChartType::Timeline,
+        ChartType::Trend,
+        ChartType::Scores,
+        ChartType::Lateness,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            ChartType::Pie => "pie",
+            ChartType::Timeline => "timeline",
+            ChartType::Trend => "trend",
+            ChartType::Scores => "scores",
+            ChartType::Lateness => "lateness",
+        }
+    }
+
+    /// Whether the chart shows one student rather than the class.
+    pub fn is_per_student(self) -> bool {
+        matches!(self, ChartType::Pie | ChartType::Timeline)
+    }
+
+    /// Describes the chart. Per-student charts need `student`.
+    pub fn build(
+        self,
+        report: &AttendanceReport,
+        summary: &ClassSummary,
+        student: Option<&StudentRecord>,
+    ) -> Option<Chart> {
+        Some(match self {
+            ChartType::Pie => student_pie(student?),
+            ChartType::Timeline => student_timeline(report, student?),
+            ChartType::Trend => session_trend(report, summary),
+            ChartType::Scores => score_histogram(report, summary),
+            ChartType::Lateness => lateness_histogram(summary),
+        })
+    }
+}
+
+impl std::str::FromStr for ChartType {
+    type Err = PresenceError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        ChartType::ALL
+            .into_iter()
+            .find(|chart| chart.key().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| {
+                let keys: Vec<&str> = ChartType::ALL.iter().map(|chart| chart.key()).collect();
+                PresenceError::config(
+                    "charts",
+                    format!("Unknown chart '{value}'. Use one of: {}.", keys.join(", ")),
+                )
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    Svg,
+    Png,
+}
+
+impl ChartFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ChartFormat::Svg => "svg",
+            ChartFormat::Png => "png",
+        }
+    }
+
+    /// The format matching a file's extension, SVG unless it is `.png`.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => ChartFormat::Png,
+            _ => ChartFormat::Svg,
+        }
+    }
+}
+
+impl std::str::FromStr for ChartFormat {
+    type Err = PresenceError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "svg" => Ok(ChartFormat::Svg),
+            "png" => Ok(ChartFormat::Png),
+            _ => Err(PresenceError::config(
+                "chart_format",
+                format!("Unknown chart format '{value}'. Use svg or png."),
+            )),
+        }
+    }
+}
+
+/// Pixel size of an exported chart, written `1200x675`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChartSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ChartSize {
+    const MIN: u32 = 100;
+    const MAX: u32 = 8192;
+}
+
+impl Default for ChartSize {
+    fn default() -> Self {
+        Self {
+            width: 1200,
+            height: 675,
+        }
+    }
+}
+
+impl std::fmt::Display for ChartSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+impl std::str::FromStr for ChartSize {
+    type Err = PresenceError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || {
+            PresenceError::config(
+                "chart_size",
+                format!(
+                    "Chart size must be WIDTHxHEIGHT in pixels, each between {} and {}.",
+                    Self::MIN,
+                    Self::MAX
+                ),
+            )
+        };
+        let (width, height) = value.trim().split_once(['x', 'X']).ok_or_else(invalid)?;
+        let parse = |side: &str| {
+            side.trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|side| (Self::MIN..=Self::MAX).contains(side))
+                .ok_or_else(invalid)
+        };
+        Ok(Self {
+            width: parse(width)?,
+            height: parse(height)?,
+        })
+    }
+}
+
+// Print palette: exported charts go on slides and paper, so they use the
+// report colours on white rather than the application's dark theme.
+const BACKGROUND: &str = "#ffffff";
+const TEXT: &str = "#073642";
+const MUTED: &str = "#657b83";
+const GRID: &str = "#eee8d5";
+const MARKED: &str = "#d33682";
+const FONT_FAMILY: &str = "DejaVu Sans, sans-serif";
+
+fn tone_color(tone: Tone) -> &'static str {
+    match tone {
+        Tone::Normal => "#859900",
+        Tone::Late => "#b58900",
+        Tone::Absent => "#dc322f",
+        Tone::Accent => "#268bd2",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes SVG elements with coordinates scaled to the output size.
+struct Svg {
+    out: String,
+    scale: f32,
+}
+
+impl Svg {
+    fn text(&mut self, x: f32, y: f32, size: f32, color: &str, anchor: &str, content: &str) {
+        let _ = writeln!(
+            self.out,
+            r#"<text x="{x:.1}" y="{y:.1}" font-size="{:.1}" fill="{color}" text-anchor="{anchor}">{}</text>"#,
+            size * self.scale,
+            escape(content)
+        );
+    }
+
+    fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, fill: &str, extra: &str) {
+        let _ = writeln!(
+            self.out,
+            r#"<rect x="{x:.1}" y="{y:.1}" width="{width:.1}" height="{height:.1}" fill="{fill}"{extra}/>"#
+        );
+    }
+
+    fn line(&mut self, from: (f32, f32), to: (f32, f32), color: &str, dashed: bool) {
+        let dash = if dashed {
+            format!(r#" stroke-dasharray="{0:.1} {0:.1}""#, 5.0 * self.scale)
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            self.out,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{color}" stroke-width="{:.1}"{dash}/>"#,
+            from.0,
+            from.1,
+            to.0,
+            to.1,
+            self.scale.max(1.0)
+        );
+    }
+}
+
+/// Plot area of a chart in output pixels.
+struct Area {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+}
+
+/// Renders a chart as an SVG document of the given size.
+pub fn to_svg(chart: &Chart, size: ChartSize) -> String {
+    let (width, height) = (size.width as f32, size.height as f32);
+    let scale = (width / 800.0).min(height / 450.0);
+    let mut svg = Svg {
+        out: String::new(),
+        scale,
+    };
+    let _ = writeln!(
+        svg.out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="{FONT_FAMILY}">"#,
+        size.width, size.height, size.width, size.height
+    );
+    svg.rect(0.0, 0.0, width, height, BACKGROUND, "");
+
+    let padding = 20.0 * scale;
+    svg.text(
+        padding,
+        padding + 18.0 * scale,
+        18.0,
+        TEXT,
+        "start",
+        &chart.title,
+    );
+    svg.text(
+        padding,
+        height - padding,
+        11.0,
+        MUTED,
+        "start",
+        &chart.caption,
+    );
+    let axis = if matches!(chart.plot, Plot::Pie(_)) {
+        0.0
+    } else {
+        44.0 * scale
+    };
+    let area = Area {
+        left: padding + axis,
+        top: padding + 44.0 * scale,
+        right: width - padding,
+        bottom: height - padding - 40.0 * scale,
+    };
+
+    match &chart.plot {
+        Plot::Pie(marks) => draw_pie(&mut svg, &area, marks),
+        Plot::Bars {
+            marks,
+            max,
+            guides,
+            show_values,
+        } => draw_bars(&mut svg, &area, marks, *max, guides, *show_values),
+        Plot::Line { marks, guides } => draw_line(&mut svg, &area, marks, guides),
+    }
+    svg.out.push_str("</svg>\n");
+    svg.out
+}
+
+fn no_data(svg: &mut Svg, area: &Area) {
+    svg.text(
+        (area.left + area.right) / 2.0,
+        (area.top + area.bottom) / 2.0,
+        16.0,
+        MUTED,
+        "middle",
+        "No attendance data",
+    );
+}
+
+fn draw_pie(svg: &mut Svg, area: &Area, marks: &[Mark]) {
+    let total: f32 = marks.iter().map(|mark| mark.value).sum();
+    if total <= 0.0 {
+        return no_data(svg, area);
+    }
+    let scale = svg.scale;
+    let radius = ((area.bottom - area.top) / 2.0).min((area.right - area.left) / 4.0);
+    let center = (
+        area.left + radius + 20.0 * scale,
+        (area.top + area.bottom) / 2.0,
+    );
+    let mut angle = -std::f32::consts::FRAC_PI_2;
+    for mark in marks.iter().filter(|mark| mark.value > 0.0) {
+        let color = tone_color(mark.tone);
+        let sweep = mark.value / total * std::f32::consts::TAU;
+        if sweep >= std::f32::consts::TAU - 0.0001 {
+            let _ = writeln!(
+                svg.out,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="{radius:.1}" fill="{color}"/>"#,
+                center.0, center.1
+            );
+        } else {
+            let point = |angle: f32| {
+                (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                )
+            };
+            let (start, end) = (point(angle), point(angle + sweep));
+            let large = u8::from(sweep > std::f32::consts::PI);
+            let _ = writeln!(
+                svg.out,
+                r#"<path d="M {:.1} {:.1} L {:.1} {:.1} A {radius:.1} {radius:.1} 0 {large} 1 {:.1} {:.1} Z" fill="{color}" stroke="{BACKGROUND}" stroke-width="{:.1}"/>"#,
+                center.0,
+                center.1,
+                start.0,
+                start.1,
+                end.0,
+                end.1,
+                2.0 * scale
+            );
+        }
+        angle += sweep;
+    }
+
+    let legend_x = center.0 + radius + 40.0 * scale;
+    for (index, mark) in marks.iter().enumerate() {
+        let y = area.top + (area.bottom - area.top) / 2.0 + (index as f32 - 1.0) * 28.0 * scale;
+        let swatch = 14.0 * scale;
+        svg.rect(
+            legend_x,
+            y - swatch,
+            swatch,
+            swatch,
+            tone_color(mark.tone),
+            "",
+        );
+        svg.text(
+            legend_x + swatch + 8.0 * scale,
+            y - 2.0 * scale,
+            13.0,
+            TEXT,
+            "start",
+            &format!(
+                "{}: {} ({:.1}%)",
+                mark.label,
+                number_label(mark.value),
+                mark.value / total * 100.0
+            ),
+        );
+    }
+}
+
+/// Labels under the x axis: every mark while they fit, else the first and last.
+fn x_labels(svg: &mut Svg, area: &Area, marks: &[Mark], x_of: impl Fn(usize) -> f32) {
+    let y = area.bottom + 16.0 * svg.scale;
+    if marks.len() <= 16 {
+        for (index, mark) in marks.iter().enumerate() {
+            svg.text(x_of(index), y, 10.0, MUTED, "middle", &mark.label);
+        }
+    } else if let (Some(first), Some(last)) = (marks.first(), marks.last()) {
+        svg.text(area.left, y, 10.0, MUTED, "start", &first.label);
+        svg.text(area.right, y, 10.0, MUTED, "end", &last.label);
+    }
+}
+
+fn y_axis(svg: &mut Svg, area: &Area, ticks: &[(f32, String)], y_of: impl Fn(f32) -> f32) {
+    for (value, label) in ticks {
+        let y = y_of(*value);
+        svg.line((area.left, y), (area.right, y), GRID, false);
+        svg.text(
+            area.left - 6.0 * svg.scale,
+            y + 4.0 * svg.scale,
+            10.0,
+            MUTED,
+            "end",
+            label,
+        );
+    }
+}
+
+fn draw_bars(
+    svg: &mut Svg,
+    area: &Area,
+    marks: &[Mark],
+    max: f32,
+    guides: &[Guide],
+    show_values: bool,
+) {
+    if marks.is_empty() || max <= 0.0 {
+        return no_data(svg, area);
+    }
+    let plot_height = area.bottom - area.top;
+    let y_of = |value: f32| area.bottom - plot_height * (value / max).clamp(0.0, 1.0);
+    let slot = (area.right - area.left) / marks.len() as f32;
+    let x_of = |index: usize| area.left + slot * (index as f32 + 0.5);
+    y_axis(
+        svg,
+        area,
+        &[
+            (0.0, "0".to_string()),
+            (max / 2.0, number_label(max / 2.0)),
+            (max, number_label(max)),
+        ],
+        y_of,
+    );
+    for guide in guides {
+        let y = y_of(guide.value);
+        svg.line(
+            (area.left, y),
+            (area.right, y),
+            tone_color(guide.tone),
+            true,
+        );
+    }
+
+    let bar_width = slot * 0.7;
+    for (index, mark) in marks.iter().enumerate() {
+        if show_values && mark.value <= 0.0 {
+            continue;
+        }
+        // Keep on-time sessions visible as a thin mark
+        let top = y_of(mark.value).min(area.bottom - 2.0 * svg.scale);
+        let mut extra = String::new();
+        if mark.faint {
+            extra.push_str(r#" fill-opacity="0.35""#);
+        }
+        if mark.marked {
+            let _ = write!(
+                extra,
+                r#" stroke="{MARKED}" stroke-width="{:.1}""#,
+                2.0 * svg.scale
+            );
+        }
+        svg.rect(
+            x_of(index) - bar_width / 2.0,
+            top,
+            bar_width,
+            area.bottom - top,
+            tone_color(mark.tone),
+            &extra,
+        );
+        if show_values && mark.value > 0.0 {
+            svg.text(
+                x_of(index),
+                top - 4.0 * svg.scale,
+                10.0,
+                TEXT,
+                "middle",
+                &number_label(mark.value),
+            );
+        }
+    }
+    svg.line(
+        (area.left, area.bottom),
+        (area.right, area.bottom),
+        MUTED,
+        false,
+    );
+    x_labels(svg, area, marks, x_of);
+}
+
+fn draw_line(svg: &mut Svg, area: &Area, marks: &[Mark], guides: &[Guide]) {
+    if marks.is_empty() {
+        return no_data(svg, area);
+    }
+    let plot_height = area.bottom - area.top;
+    let y_of = |rate: f32| area.bottom - plot_height * rate.clamp(0.0, 1.0);
+    let step = (area.right - area.left) / marks.len() as f32;
+    let x_of = |index: usize| area.left + step * (index as f32 + 0.5);
+    y_axis(
+        svg,
+        area,
+        &[
+            (0.0, "0%".to_string()),
+            (0.5, "50%".to_string()),
+            (1.0, "100%".to_string()),
+        ],
+        y_of,
+    );
+    for guide in guides {
+        let y = y_of(guide.value);
+        svg.line(
+            (area.left, y),
+            (area.right, y),
+            tone_color(guide.tone),
+            true,
+        );
+    }
+
+    let points: Vec<String> = marks
+        .iter()
+        .enumerate()
+        .map(|(index, mark)| format!("{:.1},{:.1}", x_of(index), y_of(mark.value)))
+        .collect();
+    let _ = writeln!(
+        svg.out,
+        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{:.1}"/>"#,
+        points.join(" "),
+        tone_color(Tone::Accent),
+        2.0 * svg.scale
+    );
+    for (index, mark) in marks.iter().enumerate() {
+        let _ = writeln!(
+            svg.out,
+            r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"/>"#,
+            x_of(index),
+            y_of(mark.value),
+            4.0 * svg.scale,
+            tone_color(mark.tone)
+        );
+    }
+    x_labels(svg, area, marks, x_of);
+}
+
+/// Renders a chart as a PNG image of the given size, rasterizing the same
+/// SVG [`to_svg`] writes with the bundled DejaVu Sans font.
+#[cfg(feature = "png")]
+pub fn to_png(chart: &Chart, size: ChartSize) -> Result<Vec<u8>> {
+    use resvg::{tiny_skia, usvg};
+
+    let failed = |source: Box<dyn std::error::Error + Send + Sync>| PresenceError::Chart {
+        format: ChartFormat::Png,
+        source,
+    };
+    let mut options = usvg::Options::default();
+    options
+        .fontdb_mut()
+        .load_font_data(include_bytes!("../../assets/fonts/DejaVuSans.ttf").to_vec());
+    let tree = usvg::Tree::from_str(&to_svg(chart, size), &options)
+        .map_err(|error| failed(error.into()))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width, size.height)
+        .ok_or_else(|| failed("invalid image size".into()))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::identity(),
+        &mut pixmap.as_mut(),
+    );
+    pixmap.encode_png().map_err(|error| failed(error.into()))
+}
+
+/// Renders a chart in the given format.
+pub fn render_chart(chart: &Chart, format: ChartFormat, size: ChartSize) -> Result<Vec<u8>> {
+    match format {
+        ChartFormat::Svg => Ok(to_svg(chart, size).into_bytes()),
+        #[cfg(feature = "png")]
+        ChartFormat::Png => to_png(chart, size),
+        #[cfg(not(feature = "png"))]
+        ChartFormat::Png => Err(PresenceError::Chart {
+            format,
+            source: "PNG export requires the `png` feature.".into(),
+        }),
+    }
+}
+
+/// Writes a chart to a file in the format its extension names, and returns
+/// the path written.
+pub fn export_chart(chart: &Chart, size: ChartSize, path: &Path) -> Result<PathBuf> {
+    let data = render_chart(chart, ChartFormat::from_path(path), size)?;
+    let mut file = File::create(path).map_err(|error| PresenceError::io(path, error))?;
+    file.write_all(&data)
+        .map_err(|error| PresenceError::io(path, error))?;
+    Ok(path.to_path_buf())
+}
+
+/// File name of an exported chart: `<stem>-trend.svg` for class charts and
+/// `<stem>-pie-<student>.svg` for per-student ones.
+pub fn chart_file_name(
+    stem: &str,
+    chart: ChartType,
+    student: Option<&StudentRecord>,
+    format: ChartFormat,
+) -> String {
+    let mut name = format!("{stem}-{}", chart.key());
+    if let Some(student) = student {
+        for word in student
+            .key()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            name.push('-');
+            name.push_str(&word.to_lowercase());
+        }
+    }
+    format!("{name}.{}", format.extension())
+}
+
+/// Exports charts into a folder, named by [`chart_file_name`] after
+/// `ExportOptions::file_stem`. Per-student charts are written for every
+/// student.
+pub fn export_charts(
+    report: &AttendanceReport,
+    charts: &[ChartType],
+    format: ChartFormat,
+    size: ChartSize,
+    options: &ExportOptions,
+    folder: &Path,
+) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(folder).map_err(|error| PresenceError::io(folder, error))?;
+    let summary = summarize(report);
+    let stem = options.file_stem();
+    let mut written = Vec::new();
+    for chart in charts {
+        let students: Vec<Option<&StudentRecord>> = if chart.is_per_student() {
+            report.students.iter().map(Some).collect()
+        } else {
+            vec![None]
+        };
+        for student in students {
+            let Some(description) = chart.build(report, &summary, student) else {
+                continue;
+            };
+            let path = folder.join(chart_file_name(&stem, *chart, student, format));
+            written.push(export_chart(&description, size, &path)?);
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{generate_report, parse_participants};
+    use crate::profile::Profile;
+
+    fn report() -> AttendanceReport {
+        let sessions = [
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"\nJane Roe,jane@example.com,\"10/25/23, 1:45:00 PM\"",
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/27/23, 1:31:00 PM\"",
+        ]
+        .map(|csv| parse_participants(csv.as_bytes(), "csv").unwrap());
+        let config = Profile::default().to_config();
+        generate_report(sessions.to_vec(), config).unwrap()
+    }
+
+    #[test]
+    fn test_chart_descriptions_follow_report() {
+        let report = report();
+        let summary = summarize(&report);
+        let jane = report
+            .students
+            .iter()
+            .find(|student| student.name == "Jane")
+            .unwrap();
+
+        let Plot::Bars { marks, max, .. } = student_timeline(&report, jane).plot else {
+            panic!("timeline is a bar chart");
+        };
+        assert_eq!(max, 30.0);
+        assert_eq!((marks[0].value, marks[0].tone), (15.0, Tone::Late));
+        assert!(marks[1].faint && marks[1].value == max);
+
+        let Plot::Line { marks, .. } = session_trend(&report, &summary).plot else {
+            panic!("trend is a line chart");
+        };
+        assert_eq!(marks[1].value, 0.5);
+        assert_eq!(marks[1].tone, Tone::Absent);
+
+        assert!(ChartType::Pie.build(&report, &summary, None).is_none());
+        assert_eq!("Trend".parse::<ChartType>().unwrap(), ChartType::Trend);
+        assert!("radar".parse::<ChartType>().is_err());
+
+        assert_eq!(number_label(2.0), "2");
+        assert_eq!(number_label(0.2), "0.2");
+        assert_eq!(number_label(7.5), "7.5");
+    }
+
+    #[test]
+    fn test_chart_size_and_rendering() {
+        assert_eq!(
+            "1600x900".parse::<ChartSize>().unwrap(),
+            ChartSize {
+                width: 1600,
+                height: 900
+            }
+        );
+        assert!("1600".parse::<ChartSize>().is_err());
+        assert!("0x900".parse::<ChartSize>().is_err());
+
+        let report = report();
+        let chart = score_histogram(&report, &summarize(&report));
+        let svg = to_svg(&chart, ChartSize::default());
+        assert!(svg.starts_with("<svg") && svg.contains("Score distribution"));
+        assert!(svg.contains(r#"width="1200" height="675""#));
+
+        let dir = tempfile::tempdir().unwrap();
+        let written = export_charts(
+            &report,
+            &[ChartType::Trend, ChartType::Pie],
+            ChartFormat::Svg,
+            ChartSize::default(),
+            &ExportOptions::default(),
+            dir.path(),
+        )
+        .unwrap();
+        assert_eq!(written.len(), 3);
+        assert!(dir.path().join("attendance-trend.svg").is_file());
+        assert!(dir.path().join("attendance-pie-john.svg").is_file());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_has_requested_size() {
+        let report = report();
+        let png = to_png(
+            &student_pie(&report.students[0]),
+            ChartSize {
+                width: 320,
+                height: 180,
+            },
+        )
+        .unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        // Width and height are the first fields of the IHDR chunk
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 320);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 180);
+    }
+}
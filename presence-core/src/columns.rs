@@ -0,0 +1,321 @@
+use crate::analysis::{AttendanceReport, StudentRecord};
+use crate::error::{PresenceError, Result};
+
+/// A field that can be written as a column of an exported report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportColumn {
+    Name,
+    Surname,
+    Id,
+    Email,
+    Section,
+    Normal,
+    Late,
+    Absent,
+    NormalPercent,
+    LatePercent,
+    AbsentPercent,
+    AttendanceRate,
+    Score,
+    Eligibility,
+    /// Expands to one column per session, headed by the session date.
+    Sessions,
+}
+
+impl ExportColumn {
+    pub const ALL: [ExportColumn; 15] = [
+        ExportColumn::Name,
+        ExportColumn::Surname,
+        ExportColumn::Id,
+        ExportColumn::Email,
+        ExportColumn::Section,
+        ExportColumn::Normal,
+        ExportColumn::Late,
+        ExportColumn::Absent,
+        ExportColumn::NormalPercent,
+        ExportColumn::LatePercent,
+        ExportColumn::AbsentPercent,
+        ExportColumn::AttendanceRate,
+        ExportColumn::Score,
+        ExportColumn::Eligibility,
+        ExportColumn::Sessions,
+    ];
+
+    /// Identifier used in column specifications, e.g. `name,id:Student ID,score`.
+    pub fn key(self) -> &'static str {
+        match self {
+            ExportColumn::Name => "name",
+            ExportColumn::Surname => "surname",
+            ExportColumn::Id => "id",
+            ExportColumn::Email => "email",
+            ExportColumn::Section => "section",
+            ExportColumn::Normal => "normal",
+            ExportColumn::Late => "late",
+            ExportColumn::Absent => "absent",
+            ExportColumn::NormalPercent => "normal_pct",
+            ExportColumn::LatePercent => "late_pct",
+            ExportColumn::AbsentPercent => "absent_pct",
+            ExportColumn::AttendanceRate => "rate",
+            ExportColumn::Score => "score",
+            ExportColumn::Eligibility => "eligible",
+            ExportColumn::Sessions => "sessions",
+        }
+    }
+
+    pub fn default_label(self) -> &'static str {
+        match self {
+            ExportColumn::Name => "Name",
+            ExportColumn::Surname => "Surname",
+            ExportColumn::Id => "ID",
+            ExportColumn::Email => "Email",
+            ExportColumn::Section => "Section",
+            ExportColumn::Normal => "Normal",
+            ExportColumn::Late => "Late",
+            ExportColumn::Absent => "Absent",
+            ExportColumn::NormalPercent => "Normal %",
+            ExportColumn::LatePercent => "Late %",
+            ExportColumn::AbsentPercent => "Absent %",
+            ExportColumn::AttendanceRate => "Attendance %",
+            ExportColumn::Score => "Score",
+            ExportColumn::Eligibility => "Eligible",
+            ExportColumn::Sessions => "Sessions",
+        }
+    }
+
+    /// Relative width used by table-based layouts such as the PDF writer.
+    pub fn weight(self) -> usize {
+        match self {
+            ExportColumn::Name | ExportColumn::Surname | ExportColumn::Email => 3,
+            _ => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnSpec {
+    pub column: ExportColumn,
+    /// Custom header; the column's default label is used when `None`.
+    pub label: Option<String>,
+}
+
+impl ColumnSpec {
+    pub fn header(&self) -> &str {
+        self.label
+            .as_deref()
+            .unwrap_or_else(|| self.column.default_label())
+    }
+}
+
+pub const DEFAULT_COLUMNS: &str = "name,surname,id,normal,late,absent,score";
+
+pub fn default_columns() -> Vec<ColumnSpec> {
+    parse_columns(DEFAULT_COLUMNS).expect("default columns are valid")
+}
+
+/// Parses a comma-separated column specification. Each entry is a column key,
+/// optionally followed by `:` and a custom header label.
+pub fn parse_columns(input: &str) -> Result<Vec<ColumnSpec>> {
+    let mut columns = Vec::new();
+    for entry in input.split(',') {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let (key, label) = match entry.split_once(':') {
+            Some((key, label)) => (key.trim(), Some(label.trim().to_string())),
+            None => (entry, None),
+        };
+        let column = ExportColumn::ALL
+            .into_iter()
+            .find(|column| column.key().eq_ignore_ascii_case(key))
+            .ok_or_else(|| {
+                let keys: Vec<&str> = ExportColumn::ALL.iter().map(|c| c.key()).collect();
+                PresenceError::config(
+                    "columns",
+                    format!("Unknown column '{key}'. Use one of: {}.", keys.join(", ")),
+                )
+            })?;
+        columns.push(ColumnSpec {
+            column,
+            label: label.filter(|label| !label.is_empty()),
+        });
+    }
+    if columns.is_empty() {
+        return Err(PresenceError::config(
+            "columns",
+            "Select at least one export column.",
+        ));
+    }
+    Ok(columns)
+}
+
+/// Writes columns back as a specification that [`parse_columns`] reads.
+/// Commas would split a label into two entries, so they are dropped.
+pub fn format_columns(columns: &[ColumnSpec]) -> String {
+    columns
+        .iter()
+        .map(|spec| match &spec.label {
+            Some(label) if !label.trim().is_empty() => {
+                format!("{}:{}", spec.column.key(), label.replace(',', "").trim())
+            }
+            _ => spec.column.key().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// One header cell of an export table, tagged with the column it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableHeader {
+    pub column: ExportColumn,
+    pub label: String,
+}
+
+/// Header and rows of a report laid out according to a column specification.
+/// Every writer renders this table, so all formats share the same columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportTable {
+    pub headers: Vec<TableHeader>,
+    pub rows: Vec<Vec<String>>,
+}
+
+pub fn build_table(report: &AttendanceReport, columns: &[ColumnSpec]) -> ExportTable {
+    let mut headers = Vec::new();
+    for spec in columns {
+        if spec.column == ExportColumn::Sessions {
+            for (index, date) in report.session_dates.iter().enumerate() {
+                let date = date.format("%Y-%m-%d");
+                let label = match &spec.label {
+                    Some(prefix) => format!("{prefix} {}", index + 1),
+                    None => date.to_string(),
+                };
+                headers.push(TableHeader {
+                    column: spec.column,
+                    label,
+                });
+            }
+        } else {
+            headers.push(TableHeader {
+                column: spec.column,
+                label: spec.header().to_string(),
+            });
+        }
+    }
+
+    let rows = report
+        .students
+        .iter()
+        .map(|student| {
+            let mut row = Vec::with_capacity(headers.len());
+            for spec in columns {
+                if spec.column == ExportColumn::Sessions {
+                    row.extend(
+                        student
+                            .history
+                            .iter()
+                            .map(|session| session.status.to_string()),
+                    );
+                    // Keep rows rectangular if a history is shorter than the session list.
+                    let missing = report
+                        .session_dates
+                        .len()
+                        .saturating_sub(student.history.len());
+                    row.extend(std::iter::repeat_n(String::new(), missing));
+                } else {
+                    row.push(cell_value(report, student, spec.column));
+                }
+            }
+            row
+        })
+        .collect();
+
+    ExportTable { headers, rows }
+}
+
+/// Text of one cell, as written by every export format. `Sessions` expands
+/// to several cells and yields an empty string here.
+pub fn cell_value(
+    report: &AttendanceReport,
+    student: &StudentRecord,
+    column: ExportColumn,
+) -> String {
+    let total = student.normal + student.late + student.absent;
+    let percent = |count: u32| {
+        if total == 0 {
+            "0.0%".to_string()
+        } else {
+            format!("{:.1}%", count as f32 * 100.0 / total as f32)
+        }
+    };
+    match column {
+        ExportColumn::Name => student.name.clone(),
+        ExportColumn::Surname => student.surname.clone(),
+        ExportColumn::Id => student.id.clone(),
+        ExportColumn::Email => student.email.clone(),
+        ExportColumn::Section => student.section.clone(),
+        ExportColumn::Normal => student.normal.to_string(),
+        ExportColumn::Late => student.late.to_string(),
+        ExportColumn::Absent => student.absent.to_string(),
+        ExportColumn::NormalPercent => percent(student.normal),
+        ExportColumn::LatePercent => percent(student.late),
+        ExportColumn::AbsentPercent => percent(student.absent),
+        ExportColumn::AttendanceRate => format!("{:.1}%", student.attendance_rate() * 100.0),
+        ExportColumn::Score => format!("{:.1}/{:.1}", student.score, report.total_points),
+        ExportColumn::Eligibility => {
+            if report.is_eligible(student) {
+                "Yes".to_string()
+            } else {
+                "No".to_string()
+            }
+        }
+        ExportColumn::Sessions => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_columns() {
+        let columns = parse_columns("name, id:Student ID ,EMAIL,rate").unwrap();
+        assert_eq!(columns.len(), 4);
+        assert_eq!(
+            columns[0],
+            ColumnSpec {
+                column: ExportColumn::Name,
+                label: None
+            }
+        );
+        assert_eq!(columns[1].column, ExportColumn::Id);
+        assert_eq!(columns[1].header(), "Student ID");
+        assert_eq!(columns[2].column, ExportColumn::Email);
+        assert_eq!(columns[3].header(), "Attendance %");
+
+        let error = parse_columns("name,grade").unwrap_err();
+        assert!(matches!(
+            error,
+            PresenceError::Config {
+                field: "columns",
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("Unknown column 'grade'"));
+        assert!(parse_columns(" , ").is_err());
+        assert_eq!(default_columns().len(), 7);
+    }
+
+    #[test]
+    fn test_format_columns_round_trip() {
+        let columns = parse_columns("name, id:Student ID ,EMAIL,rate").unwrap();
+        assert_eq!(format_columns(&columns), "name,id:Student ID,email,rate");
+        assert_eq!(parse_columns(&format_columns(&columns)).unwrap(), columns);
+        assert_eq!(format_columns(&default_columns()), DEFAULT_COLUMNS);
+
+        let labelled = [ColumnSpec {
+            column: ExportColumn::Score,
+            label: Some("Points, total".to_string()),
+        }];
+        assert_eq!(format_columns(&labelled), "score:Points total");
+    }
+}
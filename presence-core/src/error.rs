@@ -0,0 +1,274 @@
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::analysis::ReportFormat;
+use crate::charts::ChartFormat;
+
+/// Result type used throughout the crate.
+pub type Result<T, E = PresenceError> = std::result::Result<T, E>;
+
+/// Errors returned by every fallible operation in this crate.
+///
+/// Variants carry the context needed to point at the problem (file, line,
+/// field); the `Display` text is suitable for end users, and lower-level
+/// causes are available through `Error::source`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PresenceError {
+    /// A configuration or export setting is invalid. `field` names the
+    /// setting, e.g. `late_minutes` or `columns`.
+    Config {
+        field: &'static str,
+        message: String,
+    },
+    /// A file or directory could not be read or written.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A CSV export is malformed.
+    Csv {
+        path: Option<PathBuf>,
+        source: csv::Error,
+    },
+    /// An Excel export could not be opened or read.
+    Excel {
+        path: Option<PathBuf>,
+        source: calamine::XlsxError,
+    },
+    /// A ZIP archive of exports is corrupt or uses an unsupported compression.
+    Archive {
+        path: PathBuf,
+        source: zip::result::ZipError,
+    },
+    /// An export inside a ZIP archive is larger than the archive reader
+    /// accepts, usually a sign of a corrupt or crafted archive. `path` names
+    /// the entry as `archive.zip/entry.csv`.
+    ArchiveEntryTooLarge { path: PathBuf, limit: u64 },
+    /// An Excel export has no worksheets.
+    EmptyWorkbook { path: Option<PathBuf> },
+    /// A cell of an attendance export holds a value that cannot be interpreted.
+    InvalidValue {
+        path: Option<PathBuf>,
+        line: Option<u64>,
+        field: String,
+        value: String,
+    },
+    /// The selected source is neither a directory nor a file.
+    InvalidSource { path: PathBuf },
+    /// The selected file is not a supported attendance export.
+    UnsupportedFile { path: PathBuf },
+    /// A directory contains no attendance exports.
+    NoAttendanceFiles { path: PathBuf },
+    /// A profile file is not valid TOML/JSON, has fields of the wrong type or
+    /// could not be serialized.
+    Profile {
+        path: PathBuf,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// A project file is not valid or could not be serialized.
+    Project {
+        path: PathBuf,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// File-system notifications for a source could not be set up.
+    Watch {
+        path: PathBuf,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// Writing a report failed.
+    Export {
+        format: ReportFormat,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// Rendering a chart failed.
+    Chart {
+        format: ChartFormat,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// The user dismissed a dialog or cancelled a running load through a
+    /// `CancelToken`.
+    Cancelled,
+}
+
+impl PresenceError {
+    pub(crate) fn config(field: &'static str, message: impl Into<String>) -> Self {
+        PresenceError::Config {
+            field,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        PresenceError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub(crate) fn export(
+        format: ReportFormat,
+        source: impl Into<Box<dyn Error + Send + Sync>>,
+    ) -> Self {
+        PresenceError::Export {
+            format,
+            source: source.into(),
+        }
+    }
+
+    /// Attaches the file a parse error came from, if it does not carry one yet.
+    pub fn with_path(mut self, file: &Path) -> Self {
+        match &mut self {
+            PresenceError::Csv { path, .. }
+            | PresenceError::Excel { path, .. }
+            | PresenceError::EmptyWorkbook { path }
+            | PresenceError::InvalidValue { path, .. } => {
+                path.get_or_insert_with(|| file.to_path_buf());
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// The file the error refers to, if known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            PresenceError::Io { path, .. }
+            | PresenceError::Archive { path, .. }
+            | PresenceError::ArchiveEntryTooLarge { path, .. }
+            | PresenceError::InvalidSource { path }
+            | PresenceError::UnsupportedFile { path }
+            | PresenceError::NoAttendanceFiles { path }
+            | PresenceError::Profile { path, .. }
+            | PresenceError::Project { path, .. }
+            | PresenceError::Watch { path, .. } => Some(path),
+            PresenceError::Csv { path, .. }
+            | PresenceError::Excel { path, .. }
+            | PresenceError::EmptyWorkbook { path }
+            | PresenceError::InvalidValue { path, .. } => path.as_deref(),
+            PresenceError::Config { .. }
+            | PresenceError::Export { .. }
+            | PresenceError::Chart { .. }
+            | PresenceError::Cancelled => None,
+        }
+    }
+}
+
+fn location(path: &Option<PathBuf>, line: Option<u64>) -> String {
+    let mut location = String::new();
+    if let Some(path) = path {
+        location.push_str(&format!(" in {}", path.display()));
+    }
+    if let Some(line) = line {
+        location.push_str(&format!(" at line {line}"));
+    }
+    location
+}
+
+impl fmt::Display for PresenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresenceError::Config { message, .. } => write!(f, "{message}"),
+            PresenceError::Io { path, .. } => write!(f, "Failed to access {}", path.display()),
+            PresenceError::Csv { path, source } => {
+                let line = source.position().map(|position| position.line());
+                write!(f, "Failed to read CSV{}", location(path, line))
+            }
+            PresenceError::Excel { path, .. } => {
+                write!(f, "Failed to read Excel data{}", location(path, None))
+            }
+            PresenceError::Archive { path, .. } => {
+                write!(f, "Failed to read ZIP archive {}", path.display())
+            }
+            PresenceError::ArchiveEntryTooLarge { path, limit } => write!(
+                f,
+                "{} is larger than {} MB and was not read.",
+                path.display(),
+                limit / (1024 * 1024)
+            ),
+            PresenceError::EmptyWorkbook { path } => {
+                write!(f, "Excel file is missing sheets{}.", location(path, None))
+            }
+            PresenceError::InvalidValue {
+                path,
+                line,
+                field,
+                value,
+            } => write!(f, "Invalid {field} '{value}'{}.", location(path, *line)),
+            PresenceError::InvalidSource { .. } => {
+                write!(f, "Please select a valid directory or attendance file.")
+            }
+            PresenceError::UnsupportedFile { .. } => write!(
+                f,
+                "Selected file is not a supported CSV/XLSX attendance export or ZIP archive."
+            ),
+            PresenceError::NoAttendanceFiles { .. } => {
+                write!(
+                    f,
+                    "No attendance CSV/XLSX/ZIP files found in the directory."
+                )
+            }
+            PresenceError::Watch { path, .. } => write!(f, "Failed to watch {}", path.display()),
+            PresenceError::Project { path, .. } => {
+                write!(f, "Invalid project file {}", path.display())
+            }
+            PresenceError::Profile { path, .. } => {
+                write!(f, "Invalid profile {}", path.display())
+            }
+            PresenceError::Export { format, .. } => write!(f, "Failed to write {format} report"),
+            PresenceError::Chart { format, .. } => {
+                write!(
+                    f,
+                    "Failed to render {} chart",
+                    format.extension().to_uppercase()
+                )
+            }
+            PresenceError::Cancelled => write!(f, "Cancelled."),
+        }
+    }
+}
+
+impl Error for PresenceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PresenceError::Io { source, .. } => Some(source),
+            PresenceError::Csv { source, .. } => Some(source),
+            PresenceError::Excel { source, .. } => Some(source),
+            PresenceError::Archive { source, .. } => Some(source),
+            PresenceError::Profile { source, .. }
+            | PresenceError::Project { source, .. }
+            | PresenceError::Watch { source, .. }
+            | PresenceError::Export { source, .. }
+            | PresenceError::Chart { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_path_and_source_chain() {
+        let error = PresenceError::InvalidValue {
+            path: None,
+            line: Some(4),
+            field: "First Join".to_string(),
+            value: "yesterday".to_string(),
+        }
+        .with_path(Path::new("week-01.csv"));
+        assert_eq!(
+            error.to_string(),
+            "Invalid First Join 'yesterday' in week-01.csv at line 4."
+        );
+        assert_eq!(error.path(), Some(Path::new("week-01.csv")));
+
+        let error = PresenceError::io(
+            "missing.csv",
+            std::io::Error::new(std::io::ErrorKind::NotFound, "not found"),
+        );
+        assert_eq!(error.source().unwrap().to_string(), "not found");
+    }
+}
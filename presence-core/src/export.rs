@@ -0,0 +1,321 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::analysis::{AttendanceReport, ExportOptions, ReportFormat};
+use crate::columns::build_table;
+use crate::error::{PresenceError, Result};
+use crate::gradebook;
+#[cfg(feature = "pdf")]
+use crate::pdf::write_pdf;
+
+/// Writes a report in the given format to any destination. Nothing here
+/// touches the GUI, so it can be driven from tests, scripts or the command line.
+pub fn export_report(
+    report: &AttendanceReport,
+    format: ReportFormat,
+    options: &ExportOptions,
+    writer: impl Write,
+) -> Result<()> {
+    match format {
+        ReportFormat::Csv => write_csv(writer, report, options),
+        ReportFormat::Txt => write_text(writer, report, options),
+        #[cfg(feature = "pdf")]
+        ReportFormat::Pdf => write_pdf(writer, report, options),
+        #[cfg(not(feature = "pdf"))]
+        ReportFormat::Pdf => Err(PresenceError::export(
+            format,
+            "PDF export requires the `pdf` feature.",
+        )),
+        ReportFormat::Moodle => gradebook::write_moodle(writer, report, options),
+        ReportFormat::Canvas => gradebook::write_canvas(writer, report, options),
+        ReportFormat::Blackboard => gradebook::write_blackboard(writer, report, options),
+    }
+}
+
+/// Writes a report to a file and returns the path actually written. PDF
+/// reports always get a `.pdf` extension.
+pub fn export_report_to_path(
+    report: &AttendanceReport,
+    format: ReportFormat,
+    options: &ExportOptions,
+    path: &Path,
+) -> Result<PathBuf> {
+    let path = if format == ReportFormat::Pdf && path.extension().is_none_or(|ext| ext != "pdf") {
+        path.with_extension("pdf")
+    } else {
+        path.to_path_buf()
+    };
+    let file = File::create(&path).map_err(|error| PresenceError::io(&path, error))?;
+    let mut writer = BufWriter::new(file);
+    export_report(report, format, options, &mut writer)?;
+    writer
+        .flush()
+        .map_err(|error| PresenceError::io(&path, error))?;
+    Ok(path)
+}
+
+/// Exports several formats into one folder, naming every file after
+/// `ExportOptions::file_stem` so a bundle of reports stays recognisable.
+pub fn export_formats(
+    report: &AttendanceReport,
+    formats: &[ReportFormat],
+    options: &ExportOptions,
+    folder: &Path,
+) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(folder).map_err(|error| PresenceError::io(folder, error))?;
+    let stem = options.file_stem();
+    formats
+        .iter()
+        .map(|format| {
+            let path = folder.join(format.file_name(&stem));
+            export_report_to_path(report, *format, options, &path)
+        })
+        .collect()
+}
+
+fn write_csv(writer: impl Write, report: &AttendanceReport, options: &ExportOptions) -> Result<()> {
+    let table = build_table(report, &options.columns);
+    let mut writer = csv::Writer::from_writer(writer);
+    writer
+        .write_record(table.headers.iter().map(|header| &header.label))
+        .map_err(|error| PresenceError::export(ReportFormat::Csv, error))?;
+    for row in &table.rows {
+        writer
+            .write_record(row)
+            .map_err(|error| PresenceError::export(ReportFormat::Csv, error))?;
+    }
+    writer
+        .flush()
+        .map_err(|error| PresenceError::export(ReportFormat::Csv, error))?;
+    Ok(())
+}
+
+fn write_text(
+    mut writer: impl Write,
+    report: &AttendanceReport,
+    options: &ExportOptions,
+) -> Result<()> {
+    let table = build_table(report, &options.columns);
+    let header: Vec<&str> = table
+        .headers
+        .iter()
+        .map(|header| header.label.as_str())
+        .collect();
+    writeln!(writer, "{}", header.join("\t"))
+        .map_err(|error| PresenceError::export(ReportFormat::Txt, error))?;
+    for row in &table.rows {
+        writeln!(writer, "{}", row.join("\t"))
+            .map_err(|error| PresenceError::export(ReportFormat::Txt, error))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{ConfigValues, StudentRecord, generate_report, parse_participants};
+    use crate::columns;
+    use crate::profile::Profile;
+    use chrono::NaiveTime;
+
+    fn test_config_values() -> ConfigValues {
+        ConfigValues {
+            class_start: NaiveTime::from_hms_opt(13, 30, 0).unwrap(),
+            class_end: NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
+            late_minutes: 10,
+            absent_minutes: 30,
+            total_points: 10.0,
+            late_penalty: 0.5,
+            min_attendance: 0.7,
+        }
+    }
+
+    fn report_from_csv(csv: &str) -> AttendanceReport {
+        let config = Profile::default().to_config();
+        let session = parse_participants(csv.as_bytes(), "csv").unwrap();
+        generate_report(vec![session], config).unwrap()
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("output.csv");
+
+        let report = AttendanceReport {
+            students: vec![StudentRecord {
+                name: "John".to_string(),
+                surname: "Doe".to_string(),
+                id: "john".to_string(),
+                email: "john@example.com".to_string(),
+                section: String::new(),
+                normal: 1,
+                late: 0,
+                absent: 0,
+                score: 1.0,
+                history: Vec::new(),
+            }],
+            sessions: 1,
+            total_points: 10.0,
+            session_dates: Vec::new(),
+            session_sources: Vec::new(),
+            config: test_config_values(),
+        };
+
+        export_report_to_path(
+            &report,
+            ReportFormat::Csv,
+            &ExportOptions::default(),
+            &file_path,
+        )
+        .expect("Failed to write CSV");
+
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        assert!(content.contains("Name,Surname,ID"));
+        assert!(content.contains("John,Doe,john,1,0,0,1.0/10.0"));
+    }
+
+    #[test]
+    fn test_write_csv_custom_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = report_from_csv(
+            "Name,Email,Section,First Join\nJohn Doe,john@example.com,B,\"10/25/23, 1:45:00 PM\"",
+        );
+        let options = ExportOptions {
+            columns: columns::parse_columns("email:E-mail,id,section,late_pct,eligible,sessions")
+                .unwrap(),
+            ..ExportOptions::default()
+        };
+
+        let file_path = dir.path().join("output.csv");
+        export_report_to_path(&report, ReportFormat::Csv, &options, &file_path)
+            .expect("Failed to write CSV");
+
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        let mut lines = content.lines();
+        assert_eq!(
+            lines.next(),
+            Some("E-mail,ID,Section,Late %,Eligible,2023-10-25")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("john@example.com,john,B,100.0%,Yes,Late")
+        );
+    }
+
+    #[test]
+    fn test_write_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("output.txt");
+
+        let report = AttendanceReport {
+            students: vec![StudentRecord {
+                name: "John".to_string(),
+                surname: "Doe".to_string(),
+                id: "john".to_string(),
+                email: "john@example.com".to_string(),
+                section: String::new(),
+                normal: 1,
+                late: 0,
+                absent: 0,
+                score: 1.0,
+                history: Vec::new(),
+            }],
+            sessions: 1,
+            total_points: 10.0,
+            session_dates: Vec::new(),
+            session_sources: Vec::new(),
+            config: test_config_values(),
+        };
+
+        export_report_to_path(
+            &report,
+            ReportFormat::Txt,
+            &ExportOptions::default(),
+            &file_path,
+        )
+        .expect("Failed to write TXT");
+
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        assert!(content.contains("Name\tSurname\tID"));
+        assert!(content.contains("John\tDoe\tjohn\t1\t0\t0\t1.0/10.0"));
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn test_write_pdf_with_student_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = report_from_csv(
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"\nJane Smith,jane@example.com,\"10/25/23, 1:45:00 PM\"",
+        );
+        let options = ExportOptions {
+            course_name: "CS101".to_string(),
+            term: "Fall 2023".to_string(),
+            student_pages: true,
+            ..ExportOptions::default()
+        };
+
+        // The extension is corrected to .pdf
+        let file_path = dir.path().join("output");
+        let written = export_report_to_path(&report, ReportFormat::Pdf, &options, &file_path)
+            .expect("Failed to write PDF");
+        assert_eq!(written, dir.path().join("output.pdf"));
+
+        let content = std::fs::read(written).unwrap();
+        assert!(content.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn test_export_report_to_writer() {
+        let report = report_from_csv(
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"",
+        );
+        let mut buffer = Vec::new();
+        export_report(
+            &report,
+            ReportFormat::Txt,
+            &ExportOptions::default(),
+            &mut buffer,
+        )
+        .expect("Failed to export");
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "Name\tSurname\tID\tNormal\tLate\tAbsent\tScore\nJohn\tDoe\tjohn\t1\t0\t0\t1.0/10.0\n"
+        );
+    }
+
+    #[test]
+    fn test_export_formats_into_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = report_from_csv(
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"",
+        );
+        let options = ExportOptions {
+            course_name: "CS 101".to_string(),
+            term: "Fall/2023".to_string(),
+            ..ExportOptions::default()
+        };
+        let folder = dir.path().join("reports");
+        let written = export_formats(
+            &report,
+            &[ReportFormat::Csv, ReportFormat::Txt, ReportFormat::Canvas],
+            &options,
+            &folder,
+        )
+        .expect("Failed to export formats");
+
+        let names: Vec<String> = written
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "cs-101-fall-2023-attendance.csv",
+                "cs-101-fall-2023-attendance.txt",
+                "cs-101-fall-2023-attendance-canvas.csv",
+            ]
+        );
+        assert!(written.iter().all(|path| path.is_file()));
+    }
+}
@@ -0,0 +1,282 @@
+use std::io::Write;
+
+use crate::analysis::{AttendanceReport, ExportOptions, ReportFormat, StudentRecord};
+use crate::error::{PresenceError, Result};
+
+/// Moodle "Import grades > CSV file". The file is matched on the
+/// "Email address" column; the grade column is mapped to a grade item during import.
+pub fn write_moodle(
+    writer: impl Write,
+    report: &AttendanceReport,
+    options: &ExportOptions,
+) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer
+        .write_record([
+            "First name",
+            "Last name",
+            "ID number",
+            "Email address",
+            options.grade_item.as_str(),
+        ])
+        .map_err(|error| PresenceError::export(ReportFormat::Moodle, error))?;
+    for student in &report.students {
+        writer
+            .write_record([
+                student.name.as_str(),
+                student.surname.as_str(),
+                student.id.as_str(),
+                student.email.as_str(),
+                &format_grade(student.score),
+            ])
+            .map_err(|error| PresenceError::export(ReportFormat::Moodle, error))?;
+    }
+    writer
+        .flush()
+        .map_err(|error| PresenceError::export(ReportFormat::Moodle, error))
+}
+
+/// Canvas Gradebook "Import". Canvas expects its five identifier columns
+/// followed by one column per assignment and a "Points Possible" row. The
+/// Canvas-internal `ID` is unknown here, so students are matched on the SIS columns.
+pub fn write_canvas(
+    writer: impl Write,
+    report: &AttendanceReport,
+    options: &ExportOptions,
+) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer
+        .write_record([
+            "Student",
+            "ID",
+            "SIS User ID",
+            "SIS Login ID",
+            "Section",
+            options.grade_item.as_str(),
+        ])
+        .map_err(|error| PresenceError::export(ReportFormat::Canvas, error))?;
+    writer
+        .write_record([
+            "    Points Possible",
+            "",
+            "",
+            "",
+            "",
+            &format_grade(report.total_points),
+        ])
+        .map_err(|error| PresenceError::export(ReportFormat::Canvas, error))?;
+    for student in &report.students {
+        writer
+            .write_record([
+                sortable_name(student).as_str(),
+                "",
+                student.id.as_str(),
+                student.email.as_str(),
+                student.section.as_str(),
+                &format_grade(student.score),
+            ])
+            .map_err(|error| PresenceError::export(ReportFormat::Canvas, error))?;
+    }
+    writer
+        .flush()
+        .map_err(|error| PresenceError::export(ReportFormat::Canvas, error))
+}
+
+/// Blackboard Learn Grade Center "Upload". Rows are matched on "Username"; a
+/// grade column without a `|<column id>` suffix is created on upload.
+pub fn write_blackboard(
+    writer: impl Write,
+    report: &AttendanceReport,
+    options: &ExportOptions,
+) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(csv::QuoteStyle::Always)
+        .from_writer(writer);
+    let grade_column = format!(
+        "{} [Total Pts: {} Score]",
+        options.grade_item,
+        format_grade(report.total_points)
+    );
+    writer
+        .write_record([
+            "Last Name",
+            "First Name",
+            "Username",
+            "Student ID",
+            grade_column.as_str(),
+        ])
+        .map_err(|error| PresenceError::export(ReportFormat::Blackboard, error))?;
+    for student in &report.students {
+        writer
+            .write_record([
+                student.surname.as_str(),
+                student.name.as_str(),
+                username(student),
+                student.id.as_str(),
+                &format_grade(student.score),
+            ])
+            .map_err(|error| PresenceError::export(ReportFormat::Blackboard, error))?;
+    }
+    writer
+        .flush()
+        .map_err(|error| PresenceError::export(ReportFormat::Blackboard, error))
+}
+
+/// Canvas's "Last, First" name, or the single name of a student who only
+/// has one.
+fn sortable_name(student: &StudentRecord) -> String {
+    match (student.surname.as_str(), student.name.as_str()) {
+        (surname, name) if surname.is_empty() || name.is_empty() => {
+            format!("{surname}{name}")
+        }
+        (surname, name) => format!("{surname}, {name}"),
+    }
+}
+
+/// Login name for Blackboard: the local part of the email address, or the ID
+/// when there is no email.
+fn username(student: &StudentRecord) -> &str {
+    student
+        .email
+        .split('@')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&student.id)
+}
+
+fn format_grade(value: f32) -> String {
+    format!("{value:.2}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{generate_report, parse_participants};
+    use crate::profile::Profile;
+
+    /// Two students whose institutional IDs differ from their logins, as in
+    /// a roster imported from the LMS.
+    fn sample_report() -> AttendanceReport {
+        let config = Profile::default().to_config();
+        let session1 = "Name,Email,Section,First Join\nJohn Doe,john@example.com,A,\"10/25/23, 1:30:00 PM\"\nAnna Maria Lopez,alopez@example.com,B,\"10/25/23, 1:41:00 PM\"";
+        let session2 = "Name,Email,Section,First Join\nAnna Maria Lopez,alopez@example.com,B,\"10/27/23, 1:35:00 PM\"";
+        let sessions = vec![
+            parse_participants(session1.as_bytes(), "csv").unwrap(),
+            parse_participants(session2.as_bytes(), "csv").unwrap(),
+        ];
+        let mut report = generate_report(sessions, config).unwrap();
+        for student in &mut report.students {
+            student.id = match student.email.as_str() {
+                "john@example.com" => "S1001".to_string(),
+                _ => "S1002".to_string(),
+            };
+        }
+        report
+    }
+
+    fn render(
+        write: impl Fn(&mut Vec<u8>, &AttendanceReport, &ExportOptions) -> Result<()>,
+    ) -> String {
+        let mut buffer = Vec::new();
+        write(&mut buffer, &sample_report(), &ExportOptions::default()).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    fn rows(data: &str) -> Vec<Vec<String>> {
+        csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(data.as_bytes())
+            .records()
+            .map(|record| record.unwrap().iter().map(str::to_string).collect())
+            .collect()
+    }
+
+    /// Every identifier column written exists in the LMS template, in the
+    /// template's order. The last column written is the grade.
+    fn assert_identifiers_follow(template: &[String], header: &[String]) {
+        let positions: Vec<usize> = header[..header.len() - 1]
+            .iter()
+            .map(|column| {
+                template
+                    .iter()
+                    .position(|name| name == column)
+                    .unwrap_or_else(|| panic!("{column} is not a template column"))
+            })
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    /// Value of a column by header name, for every student row.
+    fn column(rows: &[Vec<String>], skip: usize, name: &str) -> Vec<String> {
+        let index = rows[0].iter().position(|cell| cell == name).unwrap();
+        rows[skip..].iter().map(|row| row[index].clone()).collect()
+    }
+
+    // Templates are exports of each LMS gradebook, which is what their
+    // documentation recommends as the starting point for an import.
+
+    #[test]
+    fn test_write_moodle_follows_template() {
+        let template = rows(include_str!(
+            "../tests/fixtures/gradebook/moodle_template.csv"
+        ));
+        let output = rows(&render(|w, r, o| write_moodle(w, r, o)));
+        assert_identifiers_follow(&template[0], &output[0]);
+        assert_eq!(output[0].last().unwrap(), "Attendance");
+        // Imports are matched on the email address
+        assert_eq!(
+            column(&output, 1, "Email address"),
+            ["john@example.com", "alopez@example.com"]
+        );
+        assert_eq!(column(&output, 1, "ID number"), ["S1001", "S1002"]);
+        assert_eq!(column(&output, 1, "Attendance"), ["1.00", "1.50"]);
+    }
+
+    #[test]
+    fn test_write_canvas_follows_template() {
+        let template = rows(include_str!(
+            "../tests/fixtures/gradebook/canvas_template.csv"
+        ));
+        let output = rows(&render(|w, r, o| write_canvas(w, r, o)));
+        // Canvas requires its five identifier columns first, then a points row
+        assert_eq!(output[0][..5], template[0][..5]);
+        assert_eq!(output[1][0], template[1][0]);
+        assert_eq!(output[1].len(), output[0].len());
+        assert_eq!(output[1][5], "10.00");
+        assert_eq!(output[2][0], "Doe, John");
+        assert_eq!(column(&output, 2, "SIS User ID"), ["S1001", "S1002"]);
+        assert_eq!(column(&output, 2, "Section"), ["A", "B"]);
+
+        // Students known by a single name get no dangling comma
+        let mut report = sample_report();
+        report.students[0].surname.clear();
+        let mut buffer = Vec::new();
+        write_canvas(&mut buffer, &report, &ExportOptions::default()).unwrap();
+        let output = rows(&String::from_utf8(buffer).unwrap());
+        assert_eq!(column(&output, 2, "Student"), ["John", "Maria Lopez, Anna"]);
+    }
+
+    #[test]
+    fn test_write_blackboard_follows_template() {
+        let raw = include_str!("../tests/fixtures/gradebook/blackboard_template.csv");
+        let template = rows(raw);
+        let written = render(|w, r, o| write_blackboard(w, r, o));
+        let output = rows(&written);
+        assert_identifiers_follow(&template[0], &output[0]);
+        // Every cell is quoted, like the Grade Center download
+        assert!(raw.starts_with("\"Last Name\""));
+        assert!(written.starts_with("\"Last Name\""));
+        // A new column has the template's header without the `|<column id>` suffix
+        let (existing, _) = template[0].last().unwrap().split_once(" |").unwrap();
+        let shape = |header: &str| {
+            header
+                .split_once(" [Total Pts: ")
+                .map(|(_, pts)| pts.ends_with(" Score]"))
+        };
+        assert_eq!(shape(existing), Some(true));
+        assert_eq!(shape(output[0].last().unwrap()), Some(true));
+        // Rows are matched on the username, the ID is kept separately
+        assert_eq!(column(&output, 1, "Username"), ["john", "alopez"]);
+        assert_eq!(column(&output, 1, "Student ID"), ["S1001", "S1002"]);
+    }
+}
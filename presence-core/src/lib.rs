@@ -0,0 +1,51 @@
+//! Attendance analysis engine behind Presence.
+//!
+//! Parses Zoom/Teams-style attendance exports (CSV and XLSX, loose or inside
+//! ZIP archives), classifies each participant as on time, late or absent per
+//! session, scores the class and writes reports in several formats. The crate
+//! has no GUI dependencies; PDF output is behind the default `pdf` feature, PNG
+//! charts behind the default `png` feature and file-system notifications
+//! behind the default `watch` feature.
+//!
+//! ```no_run
+//! use presence_core::{ExportOptions, ReportFormat, export, load_attendance, profile::Profile};
+//!
+//! let profile = Profile::default();
+//! let report = load_attendance("course/".into(), profile.to_config())?;
+//! export::export_report_to_path(
+//!     &report,
+//!     ReportFormat::Csv,
+//!     &ExportOptions::default(),
+//!     "attendance.csv".as_ref(),
+//! )?;
+//! # Ok::<(), presence_core::PresenceError>(())
+//! ```
+
+pub mod analysis;
+pub mod archive;
+pub mod cache;
+pub mod charts;
+pub mod columns;
+pub mod error;
+pub mod export;
+pub mod gradebook;
+pub mod loader;
+pub mod overrides;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod profile;
+pub mod project;
+pub mod watch;
+
+pub use analysis::{
+    AttendanceConfig, AttendanceReport, AttendanceStatus, ClassSummary, ConfigValues,
+    ExportOptions, LATENESS_BUCKET_MINUTES, Participant, ReportFormat, SessionAttendance,
+    StudentRecord, generate_report, parse_config, parse_participants, summarize, validate_config,
+};
+pub use cache::{CancelToken, LoadProgress, SessionCache};
+pub use charts::{Chart, ChartFormat, ChartSize, ChartType};
+pub use error::PresenceError;
+pub use loader::{
+    ScanOptions, load_attendance, load_attendance_cached, load_attendance_files,
+    load_attendance_files_with, load_attendance_sources, scan_source, scan_sources,
+};
@@ -0,0 +1,436 @@
+use std::path::{Path, PathBuf};
+
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{self, AttendanceConfig, AttendanceReport};
+use crate::archive;
+use crate::cache::{CancelToken, LoadProgress, SessionCache};
+use crate::error::{PresenceError, Result};
+
+/// Which files of a source folder are analyzed. Patterns are globs matched
+/// against the path relative to the folder, with `/` as separator, so
+/// `*_summary.csv` skips summaries in every subfolder and `week-0[1-4]/*`
+/// selects the first four weeks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    /// Descend into subfolders.
+    pub recursive: bool,
+    /// A file must match one of these; an empty list selects every export.
+    pub include: Vec<String>,
+    /// Files matching any of these are skipped.
+    pub exclude: Vec<String>,
+}
+
+impl ScanOptions {
+    /// Splits a comma-separated pattern list, as typed into an input field.
+    pub fn split_patterns(input: &str) -> Vec<String> {
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Loads every attendance export in a directory (or a single export or ZIP
+/// archive) and generates the report for it. Exports inside archives are
+/// read in memory, without extracting them.
+pub fn load_attendance(directory: PathBuf, config: AttendanceConfig) -> Result<AttendanceReport> {
+    load_attendance_cached(directory, config, &mut SessionCache::new())
+}
+
+/// Like [`load_attendance`], but reuses sessions parsed by earlier runs and
+/// only parses files that are new or changed.
+pub fn load_attendance_cached(
+    directory: PathBuf,
+    config: AttendanceConfig,
+    cache: &mut SessionCache,
+) -> Result<AttendanceReport> {
+    let files = attendance_files(directory)?;
+    load_attendance_files(&files, config, cache)
+}
+
+/// Loads the exports of several sources into one report, e.g. a folder of
+/// Zoom exports and a few hand-made CSVs for in-person sessions. The sources
+/// are scanned as by [`scan_sources`].
+pub fn load_attendance_sources(
+    sources: &[PathBuf],
+    options: &ScanOptions,
+    config: AttendanceConfig,
+    cache: &mut SessionCache,
+) -> Result<AttendanceReport> {
+    let files = scan_sources(sources, options)?;
+    load_attendance_files(&files, config, cache)
+}
+
+/// Generates the report for an explicit list of exports, e.g. the files
+/// returned by [`scan_source`] after the user confirmed them.
+pub fn load_attendance_files(
+    files: &[PathBuf],
+    config: AttendanceConfig,
+    cache: &mut SessionCache,
+) -> Result<AttendanceReport> {
+    load_attendance_files_with(files, config, cache, &CancelToken::new(), |_| {})
+}
+
+/// Like [`load_attendance_files`], reporting each parsed file to
+/// `on_progress` and giving up with `PresenceError::Cancelled` once `cancel`
+/// is set. Files are parsed in parallel.
+pub fn load_attendance_files_with(
+    files: &[PathBuf],
+    config: AttendanceConfig,
+    cache: &mut SessionCache,
+    cancel: &CancelToken,
+    on_progress: impl FnMut(&LoadProgress),
+) -> Result<AttendanceReport> {
+    let (sources, sessions): (Vec<PathBuf>, Vec<_>) = cache
+        .sessions_with(files, cancel, on_progress)?
+        .into_iter()
+        .filter(|(_, participants)| !participants.is_empty())
+        .unzip();
+    let mut report = analysis::generate_report(sessions, config)?;
+    report.session_sources = sources;
+    Ok(report)
+}
+
+/// The attendance exports and archives directly inside a directory, sorted by
+/// path, or the file itself when a single export or archive is selected.
+pub fn attendance_files(directory: PathBuf) -> Result<Vec<PathBuf>> {
+    scan_source(&directory, &ScanOptions::default())
+}
+
+/// The attendance exports of a source selected by `options`, sorted by path.
+/// A single selected file is returned as is; the patterns only filter folders.
+pub fn scan_source(source: &Path, options: &ScanOptions) -> Result<Vec<PathBuf>> {
+    if source.is_file() {
+        return if is_source_file(source) {
+            Ok(vec![source.to_path_buf()])
+        } else {
+            Err(PresenceError::UnsupportedFile {
+                path: source.to_path_buf(),
+            })
+        };
+    }
+    if !source.is_dir() {
+        return Err(PresenceError::InvalidSource {
+            path: source.to_path_buf(),
+        });
+    }
+
+    let include = compile_patterns(&options.include, "include")?;
+    let exclude = compile_patterns(&options.exclude, "exclude")?;
+    let mut files = Vec::new();
+    let mut folders = vec![source.to_path_buf()];
+    while let Some(folder) = folders.pop() {
+        let entries =
+            std::fs::read_dir(&folder).map_err(|error| PresenceError::io(&folder, error))?;
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            // Symlinked folders are not followed, which rules out cycles.
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                if options.recursive {
+                    folders.push(path);
+                }
+                continue;
+            }
+            if !is_source_file(&path) {
+                continue;
+            }
+            let relative = relative_pattern_path(source, &path);
+            let included = include.is_empty() || include.iter().any(|p| p.matches(&relative));
+            if included && !exclude.iter().any(|p| p.matches(&relative)) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+
+    if files.is_empty() {
+        return Err(PresenceError::NoAttendanceFiles {
+            path: source.to_path_buf(),
+        });
+    }
+    Ok(files)
+}
+
+/// The exports of several sources combined into one list, in source order.
+/// Each source is scanned as by [`scan_source`]; files reached through more
+/// than one source are kept once.
+pub fn scan_sources(sources: &[PathBuf], options: &ScanOptions) -> Result<Vec<PathBuf>> {
+    if sources.is_empty() {
+        return Err(PresenceError::InvalidSource {
+            path: PathBuf::new(),
+        });
+    }
+    let mut seen = std::collections::HashSet::new();
+    let mut files = Vec::new();
+    for source in sources {
+        for file in scan_source(source, options)? {
+            if seen.insert(file.clone()) {
+                files.push(file);
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn compile_patterns(patterns: &[String], field: &'static str) -> Result<Vec<Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Pattern::new(pattern).map_err(|error| {
+                PresenceError::config(
+                    field,
+                    format!("Invalid pattern '{pattern}': {}.", error.msg),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Path of a file relative to the scanned folder, with `/` separators.
+fn relative_pattern_path(source: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(source).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_source_file(path: &Path) -> bool {
+    is_attendance_file(path) || archive::is_archive(path)
+}
+
+/// Whether a path has one of the supported export extensions.
+pub fn is_attendance_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("csv") | Some("xlsx") | Some("xls")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::Profile;
+    use std::fs::File;
+    use std::io::Write;
+
+    #[test]
+    fn test_load_attendance() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("session1.csv");
+        let mut file = File::create(&file_path).unwrap();
+        // Quote the date field as per CSV requirement for commas
+        writeln!(
+            file,
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\""
+        )
+        .unwrap();
+
+        let config = AttendanceConfig {
+            class_start: "13:30".to_string(),
+            class_end: "15:00".to_string(),
+            late_minutes: "10".to_string(),
+            absent_minutes: "30".to_string(),
+            total_points: "10.0".to_string(),
+            late_penalty: "0.5".to_string(),
+            min_attendance: "70".to_string(),
+        };
+
+        // Test loading from directory
+        let report = load_attendance(dir.path().to_path_buf(), config.clone())
+            .expect("Failed to load from dir");
+        assert_eq!(report.sessions, 1);
+        assert_eq!(report.students.len(), 1);
+
+        // Test loading from single file
+        let report_file = load_attendance(file_path, config).expect("Failed to load from file");
+        assert_eq!(report_file.sessions, 1);
+        assert_eq!(report_file.students.len(), 1);
+    }
+
+    #[test]
+    fn test_load_attendance_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("session1.csv");
+        std::fs::write(
+            &file_path,
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"",
+        )
+        .unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let in_person = other.path().join("in-person.csv");
+        std::fs::write(
+            &in_person,
+            "Name,Email,First Join\nJane Roe,jane@example.com,\"10/27/23, 1:30:00 PM\"",
+        )
+        .unwrap();
+
+        // A folder, a file kept elsewhere and a file already in the folder
+        let report = load_attendance_sources(
+            &[dir.path().to_path_buf(), in_person.clone(), file_path],
+            &ScanOptions::default(),
+            Profile::default().to_config(),
+            &mut SessionCache::new(),
+        )
+        .expect("Failed to load sources");
+        assert_eq!(report.sessions, 2);
+        assert_eq!(report.students.len(), 2);
+        assert_eq!(report.session_sources[1], in_person);
+    }
+
+    #[test]
+    fn test_load_attendance_cached_reclassifies_without_parsing() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("session1.csv"),
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:45:00 PM\"",
+        )
+        .unwrap();
+        let mut config = Profile::default().to_config();
+
+        let mut cache = SessionCache::new();
+        let report =
+            load_attendance_cached(dir.path().to_path_buf(), config.clone(), &mut cache).unwrap();
+        assert_eq!(report.students[0].late, 1);
+        assert_eq!(cache.parsed(), 1);
+
+        config.late_minutes = "20".to_string();
+        let report = load_attendance_cached(dir.path().to_path_buf(), config, &mut cache).unwrap();
+        assert_eq!(report.students[0].normal, 1);
+        assert_eq!((cache.parsed(), cache.reused()), (0, 1));
+    }
+
+    #[test]
+    fn test_scan_source_recursive_with_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "week-01/session.csv",
+            "week-01/session_summary.csv",
+            "week-02/session.xlsx",
+            "week-02/extra/late.csv",
+            "root.csv",
+            "week-02/notes.txt",
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            File::create(path).unwrap();
+        }
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|path| relative_pattern_path(dir.path(), path))
+                .collect()
+        };
+
+        let flat = scan_source(dir.path(), &ScanOptions::default()).unwrap();
+        assert_eq!(names(flat), vec!["root.csv"]);
+
+        let options = ScanOptions {
+            recursive: true,
+            include: Vec::new(),
+            exclude: ScanOptions::split_patterns("*_summary.csv, "),
+        };
+        assert_eq!(
+            names(scan_source(dir.path(), &options).unwrap()),
+            vec![
+                "root.csv",
+                "week-01/session.csv",
+                "week-02/extra/late.csv",
+                "week-02/session.xlsx"
+            ]
+        );
+
+        let options = ScanOptions {
+            include: vec!["week-02/*.xlsx".to_string()],
+            ..options
+        };
+        assert_eq!(
+            names(scan_source(dir.path(), &options).unwrap()),
+            vec!["week-02/session.xlsx"]
+        );
+
+        let sources = [
+            dir.path().join("week-01"),
+            dir.path().join("root.csv"),
+            dir.path().join("week-01"),
+        ];
+        assert_eq!(
+            names(scan_sources(&sources, &ScanOptions::default()).unwrap()),
+            vec![
+                "week-01/session.csv",
+                "week-01/session_summary.csv",
+                "root.csv"
+            ]
+        );
+        assert!(scan_sources(&[], &ScanOptions::default()).is_err());
+
+        let options = ScanOptions {
+            exclude: vec!["[".to_string()],
+            ..options
+        };
+        assert!(matches!(
+            scan_source(dir.path(), &options),
+            Err(PresenceError::Config {
+                field: "exclude",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_load_attendance_errors() {
+        let config = AttendanceConfig {
+            class_start: "13:30".to_string(),
+            class_end: "15:00".to_string(),
+            late_minutes: "10".to_string(),
+            absent_minutes: "30".to_string(),
+            total_points: "10.0".to_string(),
+            late_penalty: "0.5".to_string(),
+            min_attendance: "70".to_string(),
+        };
+
+        // Test non-existent directory
+        let result = load_attendance(PathBuf::from("non_existent_dir_12345"), config.clone());
+        assert!(matches!(result, Err(PresenceError::InvalidSource { .. })));
+
+        // Test directory with no valid files
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("readme.txt");
+        File::create(&file_path).unwrap();
+
+        let result = load_attendance(dir.path().to_path_buf(), config.clone());
+        assert!(matches!(
+            result,
+            Err(PresenceError::NoAttendanceFiles { .. })
+        ));
+
+        // Test invalid file type selected directly
+        let file_path = dir.path().join("invalid.txt");
+        File::create(&file_path).unwrap();
+        let result = load_attendance(file_path, config.clone());
+        assert!(matches!(result, Err(PresenceError::UnsupportedFile { .. })));
+    }
+
+    #[test]
+    fn test_parse_error_names_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("session1.csv");
+        std::fs::write(
+            &file_path,
+            "Name,Email,First Join\nJohn Doe,john@example.com,soon",
+        )
+        .unwrap();
+        let error = load_attendance(file_path.clone(), Profile::default().to_config()).unwrap_err();
+        assert!(matches!(
+            error,
+            PresenceError::InvalidValue { line: Some(2), .. }
+        ));
+        assert_eq!(error.path(), Some(file_path.as_path()));
+    }
+}
@@ -0,0 +1,424 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{self, AttendanceReport, AttendanceStatus, ReportFormat};
+use crate::error::{PresenceError, Result};
+
+/// A manual correction of one student's status in one session, e.g. when the
+/// student was in the room but the meeting software lost them.
+///
+/// Overrides are kept apart from the exports (in the project file) and laid
+/// over a generated report with [`apply_overrides`], so re-analyzing never
+/// loses them and the original result stays visible.
+///
+/// The list of overrides is an append-only log: changing an override adds a
+/// newer entry and removing one adds a withdrawal, so the audit log shows every
+/// decision. The latest entry for a session is the one in effect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusOverride {
+    /// The student's key, see `StudentRecord::key`.
+    pub student: String,
+    pub date: NaiveDate,
+    /// Export the session was read from, telling apart several sessions on
+    /// one day. `None` overrides every session on `date`, as in projects
+    /// saved before sources were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+    /// The corrected status; `None` withdraws the earlier override of the
+    /// session, restoring the computed status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<AttendanceStatus>,
+    pub reason: String,
+    /// When the entry was recorded, in local time.
+    pub recorded: NaiveDateTime,
+    /// Login of the user who recorded the entry, if known.
+    #[serde(default)]
+    pub author: String,
+}
+
+impl StatusOverride {
+    /// Records an override now. The reason is mandatory.
+    pub fn new(
+        student: impl Into<String>,
+        date: NaiveDate,
+        source: Option<PathBuf>,
+        status: AttendanceStatus,
+        reason: &str,
+    ) -> Result<Self> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(PresenceError::config(
+                "reason",
+                "Enter a reason for the override.",
+            ));
+        }
+        Ok(Self::record(
+            student.into(),
+            date,
+            source,
+            Some(status),
+            reason,
+        ))
+    }
+
+    fn record(
+        student: String,
+        date: NaiveDate,
+        source: Option<PathBuf>,
+        status: Option<AttendanceStatus>,
+        reason: &str,
+    ) -> Self {
+        Self {
+            student,
+            date,
+            source,
+            status,
+            reason: reason.to_string(),
+            recorded: Local::now().naive_local(),
+            author: current_user(),
+        }
+    }
+
+    /// Whether this override is for the given session of a student. A
+    /// missing source on either side matches any session on the date.
+    fn applies_to(&self, student: &str, date: NaiveDate, source: Option<&Path>) -> bool {
+        self.student == student
+            && self.date == date
+            && match (self.source.as_deref(), source) {
+                (Some(own), Some(session)) => own == session,
+                _ => true,
+            }
+    }
+
+    /// Whether both entries are about exactly the same session.
+    fn same_session(&self, other: &StatusOverride) -> bool {
+        self.student == other.student && self.date == other.date && self.source == other.source
+    }
+}
+
+fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+/// Adds an override to the log. Earlier entries for the session stay in the
+/// log; the new one takes effect.
+pub fn set_override(overrides: &mut Vec<StatusOverride>, new: StatusOverride) {
+    overrides.push(new);
+}
+
+/// Withdraws the override of a student's session by adding a withdrawal entry
+/// to the log. Returns whether an override was in effect.
+pub fn remove_override(
+    overrides: &mut Vec<StatusOverride>,
+    student: &str,
+    date: NaiveDate,
+    source: Option<&Path>,
+    reason: &str,
+) -> bool {
+    let Some(current) = find_override(overrides, student, date, source) else {
+        return false;
+    };
+    let reason = match reason.trim() {
+        "" => "Override removed",
+        reason => reason,
+    };
+    let withdrawal = StatusOverride::record(
+        current.student.clone(),
+        current.date,
+        current.source.clone(),
+        None,
+        reason,
+    );
+    overrides.push(withdrawal);
+    true
+}
+
+/// The override in effect for a student's session, if any: the latest entry
+/// for it, unless that entry withdrew the override.
+pub fn find_override<'a>(
+    overrides: &'a [StatusOverride],
+    student: &str,
+    date: NaiveDate,
+    source: Option<&Path>,
+) -> Option<&'a StatusOverride> {
+    overrides
+        .iter()
+        .rev()
+        .find(|entry| entry.applies_to(student, date, source))
+        .filter(|entry| entry.status.is_some())
+}
+
+/// The overrides in effect, oldest first, leaving out replaced and
+/// withdrawn entries.
+pub fn active_overrides(overrides: &[StatusOverride]) -> Vec<&StatusOverride> {
+    overrides
+        .iter()
+        .enumerate()
+        .filter(|(index, entry)| {
+            entry.status.is_some()
+                && !overrides[index + 1..]
+                    .iter()
+                    .any(|later| later.same_session(entry))
+        })
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// Lays `overrides` over a report, recounting and rescoring the students they
+/// touch. Overridden sessions keep the computed status in
+/// `SessionAttendance::original`. Applying again with a different list first
+/// restores the computed statuses, so the report always reflects exactly
+/// `overrides`.
+pub fn apply_overrides(report: &mut AttendanceReport, overrides: &[StatusOverride]) {
+    for student in &mut report.students {
+        let key = student.key();
+        let mut touched = false;
+        for (index, session) in student.history.iter_mut().enumerate() {
+            if let Some(original) = session.original.take() {
+                session.status = original;
+                touched = true;
+            }
+            let source = report.session_sources.get(index).map(PathBuf::as_path);
+            if let Some(status) =
+                find_override(overrides, &key, session.date, source).and_then(|entry| entry.status)
+            {
+                session.original = Some(session.status);
+                session.status = status;
+                touched = true;
+            }
+        }
+        if !touched {
+            continue;
+        }
+        let count = |status| {
+            student
+                .history
+                .iter()
+                .filter(|session| session.status == status)
+                .count() as u32
+        };
+        student.normal = count(AttendanceStatus::Normal);
+        student.late = count(AttendanceStatus::Late);
+        student.absent = count(AttendanceStatus::Absent);
+        student.score = analysis::calculate_score(student, &report.config);
+    }
+}
+
+/// Writes every entry of the override log as a CSV audit log, oldest first,
+/// with the student's name and the computed status taken from `report`. The
+/// action tells whether an entry set, changed or removed an override.
+pub fn write_audit_log(
+    writer: impl Write,
+    report: &AttendanceReport,
+    overrides: &[StatusOverride],
+) -> Result<()> {
+    let failed = |error| PresenceError::export(ReportFormat::Csv, error);
+    let mut entries: Vec<&StatusOverride> = overrides.iter().collect();
+    entries.sort_by_key(|entry| entry.recorded);
+
+    let mut writer = csv::Writer::from_writer(writer);
+    writer
+        .write_record([
+            "Recorded", "By", "Student", "Name", "Session", "Source", "Computed", "Action",
+            "Override", "Reason",
+        ])
+        .map_err(failed)?;
+    for (index, entry) in entries.iter().enumerate() {
+        let student = report
+            .students
+            .iter()
+            .find(|student| student.key() == entry.student);
+        let name = student
+            .map(|student| format!("{} {}", student.name, student.surname))
+            .unwrap_or_default();
+        let computed = student
+            .and_then(|student| {
+                student.history.iter().enumerate().find(|(index, session)| {
+                    let source = report.session_sources.get(*index).map(PathBuf::as_path);
+                    entry.applies_to(&entry.student, session.date, source)
+                })
+            })
+            .map(|(_, session)| session)
+            .map(|session| session.original.unwrap_or(session.status).to_string())
+            .unwrap_or_default();
+        let previous = entries[..index]
+            .iter()
+            .rev()
+            .find(|earlier| earlier.same_session(entry))
+            .and_then(|earlier| earlier.status);
+        let action = match (entry.status, previous) {
+            (None, _) => "Removed",
+            (Some(_), None) => "Set",
+            (Some(_), Some(_)) => "Changed",
+        };
+        writer
+            .write_record([
+                entry.recorded.format("%Y-%m-%d %H:%M:%S").to_string(),
+                entry.author.clone(),
+                entry.student.clone(),
+                name,
+                entry.date.to_string(),
+                entry
+                    .source
+                    .as_ref()
+                    .map(|source| source.display().to_string())
+                    .unwrap_or_default(),
+                computed,
+                action.to_string(),
+                entry
+                    .status
+                    .map(|status| status.to_string())
+                    .unwrap_or_default(),
+                entry.reason.clone(),
+            ])
+            .map_err(failed)?;
+    }
+    writer
+        .flush()
+        .map_err(|error| PresenceError::export(ReportFormat::Csv, error))
+}
+
+/// Writes the audit log to a file.
+pub fn audit_log_to_path(
+    report: &AttendanceReport,
+    overrides: &[StatusOverride],
+    path: &Path,
+) -> Result<()> {
+    let file = File::create(path).map_err(|error| PresenceError::io(path, error))?;
+    write_audit_log(file, report, overrides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{AttendanceConfig, generate_report, parse_participants};
+    use crate::profile::Profile;
+
+    fn report() -> AttendanceReport {
+        let sessions = [
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"",
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/27/23, 2:30:00 PM\"",
+        ]
+        .map(|csv| parse_participants(csv.as_bytes(), "csv").unwrap());
+        let config = Profile::default().to_config();
+        generate_report(sessions.to_vec(), config).unwrap()
+    }
+
+    #[test]
+    fn test_apply_overrides_rescores_and_restores() {
+        let mut report = report();
+        let student = report.students[0].key();
+        let date = report.session_dates[1];
+        assert_eq!(report.students[0].absent, 1);
+        assert!(StatusOverride::new(&student, date, None, AttendanceStatus::Normal, " ").is_err());
+
+        let mut overrides = Vec::new();
+        set_override(
+            &mut overrides,
+            StatusOverride::new(&student, date, None, AttendanceStatus::Late, "Zoom crashed")
+                .unwrap(),
+        );
+        set_override(
+            &mut overrides,
+            StatusOverride::new(
+                &student,
+                date,
+                None,
+                AttendanceStatus::Normal,
+                "In the room",
+            )
+            .unwrap(),
+        );
+        // The replaced override stays in the log
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(active_overrides(&overrides), vec![&overrides[1]]);
+
+        apply_overrides(&mut report, &overrides);
+        let record = &report.students[0];
+        assert_eq!((record.normal, record.absent), (2, 0));
+        assert_eq!(record.score, 2.0);
+        assert_eq!(record.history[1].original, Some(AttendanceStatus::Absent));
+        assert!(record.has_overrides());
+
+        assert!(remove_override(&mut overrides, &student, date, None, ""));
+        assert!(!remove_override(&mut overrides, &student, date, None, ""));
+        assert_eq!(overrides.len(), 3);
+        assert!(active_overrides(&overrides).is_empty());
+        apply_overrides(&mut report, &overrides);
+        let record = &report.students[0];
+        assert_eq!((record.normal, record.absent), (1, 1));
+        assert!(!record.has_overrides());
+
+        let mut log = Vec::new();
+        write_audit_log(&mut log, &report, &overrides).unwrap();
+        let log = String::from_utf8(log).unwrap();
+        let actions: Vec<&str> = log
+            .lines()
+            .skip(1)
+            .map(|line| line.split_once(",john,").unwrap().1)
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                "John Doe,2023-10-27,,Absent,Set,Late,Zoom crashed",
+                "John Doe,2023-10-27,,Absent,Changed,Normal,In the room",
+                "John Doe,2023-10-27,,Absent,Removed,,Override removed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_override_targets_one_session_of_the_day() {
+        let sessions = [
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 9:30:00 AM\"",
+            "Name,Email,First Join\nJane Roe,jane@example.com,\"10/25/23, 1:30:00 PM\"",
+        ]
+        .map(|csv| parse_participants(csv.as_bytes(), "csv").unwrap());
+        let config = AttendanceConfig {
+            class_start: "09:30".to_string(),
+            absent_minutes: "300".to_string(),
+            ..Profile::default().to_config()
+        };
+        let mut report = generate_report(sessions.to_vec(), config).unwrap();
+        report.session_sources = vec!["morning.csv".into(), "afternoon.csv".into()];
+        let john = report
+            .students
+            .iter()
+            .position(|student| student.email == "john@example.com")
+            .unwrap();
+        assert_eq!(
+            report.students[john].history[1].status,
+            AttendanceStatus::Absent
+        );
+
+        let date = report.session_dates[1];
+        let mut overrides = Vec::new();
+        set_override(
+            &mut overrides,
+            StatusOverride::new(
+                report.students[john].key(),
+                date,
+                Some("afternoon.csv".into()),
+                AttendanceStatus::Normal,
+                "Left the laptop closed",
+            )
+            .unwrap(),
+        );
+        apply_overrides(&mut report, &overrides);
+        let history = &report.students[john].history;
+        assert_eq!(history[0].original, None);
+        assert_eq!(history[1].original, Some(AttendanceStatus::Absent));
+        assert_eq!(history[1].status, AttendanceStatus::Normal);
+
+        let mut log = Vec::new();
+        write_audit_log(&mut log, &report, &overrides).unwrap();
+        let log = String::from_utf8(log).unwrap();
+        assert!(log.contains(",2023-10-25,afternoon.csv,Absent,Set,Normal,"));
+    }
+}
@@ -0,0 +1,422 @@
+use std::io::Write;
+
+use genpdf::elements::{Break, PaddedElement, PageBreak, Paragraph, TableLayout};
+use genpdf::render::Area;
+use genpdf::style::{Color, Style};
+use genpdf::{Alignment, Context, Element, Mm, Position, RenderResult, Size};
+
+use crate::analysis::{
+    self, AttendanceReport, AttendanceStatus, ExportOptions, ReportFormat, SessionAttendance,
+    StudentRecord,
+};
+use crate::columns::build_table;
+use crate::error::{PresenceError, Result};
+
+const BLUE: Color = Color::Rgb(38, 139, 210);
+const VIOLET: Color = Color::Rgb(108, 113, 196);
+const BASE01: Color = Color::Rgb(88, 110, 117);
+const BASE00: Color = Color::Rgb(101, 123, 131);
+const GREEN: Color = Color::Rgb(133, 153, 0);
+const YELLOW: Color = Color::Rgb(181, 137, 0);
+const RED: Color = Color::Rgb(220, 50, 47);
+
+const DATE_FORMAT: &str = "%d %b %Y";
+
+pub fn write_pdf(
+    writer: impl Write,
+    report: &AttendanceReport,
+    options: &ExportOptions,
+) -> Result<()> {
+    let failed = |error| PresenceError::export(ReportFormat::Pdf, error);
+    let mut doc = new_document().map_err(failed)?;
+    push_cover_page(&mut doc, report, options).map_err(failed)?;
+    doc.push(PageBreak::new());
+    push_summary_page(&mut doc, report).map_err(failed)?;
+    doc.push(PageBreak::new());
+    push_student_table(&mut doc, report, options).map_err(failed)?;
+    if options.student_pages {
+        for student in &report.students {
+            doc.push(PageBreak::new());
+            push_student_page(&mut doc, report, student).map_err(failed)?;
+        }
+    }
+    doc.render(writer).map_err(failed)
+}
+
+fn new_document() -> Result<genpdf::Document, genpdf::error::Error> {
+    let font_data = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+    let bold_data = include_bytes!("../../assets/fonts/DejaVuSans-Bold.ttf");
+
+    let font = genpdf::fonts::FontData::new(font_data.to_vec(), None)?;
+    let bold_font = genpdf::fonts::FontData::new(bold_data.to_vec(), None)?;
+
+    let font_family = genpdf::fonts::FontFamily {
+        regular: font.clone(),
+        bold: bold_font.clone(),
+        italic: font,
+        bold_italic: bold_font,
+    };
+    let mut doc = genpdf::Document::new(font_family);
+    doc.set_title("Attendance Report");
+    Ok(doc)
+}
+
+fn push_cover_page(
+    doc: &mut genpdf::Document,
+    report: &AttendanceReport,
+    options: &ExportOptions,
+) -> Result<(), genpdf::error::Error> {
+    doc.push(Break::new(6.0));
+    push_heading(doc, "Attendance Report", 24);
+    if !options.course_name.trim().is_empty() {
+        let mut course = Paragraph::new(options.course_name.trim());
+        course.set_alignment(Alignment::Center);
+        doc.push(course.styled(Style::new().with_color(BLUE).with_font_size(18).bold()));
+    }
+    if !options.term.trim().is_empty() {
+        let mut term = Paragraph::new(options.term.trim());
+        term.set_alignment(Alignment::Center);
+        doc.push(term.styled(Style::new().with_color(BASE00).with_font_size(14)));
+    }
+    doc.push(Break::new(1.0));
+    let range = match report.date_range() {
+        Some((first, last)) => format!(
+            "{} \u{2013} {}",
+            first.format(DATE_FORMAT),
+            last.format(DATE_FORMAT)
+        ),
+        None => "No sessions".to_string(),
+    };
+    let mut dates = Paragraph::new(format!("{} sessions, {range}", report.sessions));
+    dates.set_alignment(Alignment::Center);
+    doc.push(dates.styled(Style::new().with_color(BASE01).with_font_size(12)));
+    doc.push(Break::new(3.0));
+
+    let config = &report.config;
+    let settings = [
+        (
+            "Class time",
+            format!(
+                "{} \u{2013} {}",
+                config.class_start.format("%H:%M"),
+                config.class_end.format("%H:%M")
+            ),
+        ),
+        ("Late after", format!("{} minutes", config.late_minutes)),
+        ("Absent after", format!("{} minutes", config.absent_minutes)),
+        ("Total points", format!("{:.1}", config.total_points)),
+        (
+            "Credit per late session",
+            format!("{:.0}% of a session", config.late_penalty * 100.0),
+        ),
+        (
+            "Minimum attendance",
+            format!("{:.0}%", config.min_attendance * 100.0),
+        ),
+    ];
+    let mut table = TableLayout::new(vec![1, 1]);
+    table.set_cell_decorator(genpdf::elements::FrameCellDecorator::new(true, true, true));
+    for (label, value) in settings {
+        table
+            .row()
+            .element(padded_text(label, Style::new().with_color(BLUE).bold()))
+            .element(padded_text(value, Style::new().with_color(BASE01)))
+            .push()?;
+    }
+    push_subheading(doc, "Configuration");
+    doc.push(table);
+    doc.push(Break::new(2.0));
+    let mut generated = Paragraph::new(format!(
+        "Generated {}",
+        chrono::Local::now().format("%d %b %Y %H:%M")
+    ));
+    generated.set_alignment(Alignment::Center);
+    doc.push(generated.styled(Style::new().with_color(BASE00).with_font_size(9)));
+    Ok(())
+}
+
+fn push_summary_page(
+    doc: &mut genpdf::Document,
+    report: &AttendanceReport,
+) -> Result<(), genpdf::error::Error> {
+    let summary = analysis::summarize(report);
+    push_heading(doc, "Class Summary", 20);
+    doc.push(Break::new(1.0));
+
+    let percent = |rate: f32| format!("{:.1}%", rate * 100.0);
+    let rows = [
+        ("Students", summary.students.to_string()),
+        ("Sessions", summary.sessions.to_string()),
+        ("Mean attendance rate", percent(summary.mean_rate)),
+        ("Median attendance rate", percent(summary.median_rate)),
+        ("Lowest attendance rate", percent(summary.min_rate)),
+        ("Highest attendance rate", percent(summary.max_rate)),
+        (
+            "Eligible students",
+            format!("{} of {}", summary.eligible, summary.students),
+        ),
+        (
+            "Mean score",
+            format!("{:.1}/{:.1}", summary.mean_score, report.total_points),
+        ),
+    ];
+    let mut table = TableLayout::new(vec![2, 1]);
+    table.set_cell_decorator(genpdf::elements::FrameCellDecorator::new(true, true, true));
+    for (label, value) in rows {
+        table
+            .row()
+            .element(padded_text(label, Style::new().with_color(BLUE).bold()))
+            .element(padded_text(value, Style::new().with_color(BASE01)))
+            .push()?;
+    }
+    doc.push(table);
+    doc.push(Break::new(2.0));
+    push_subheading(doc, "Students by attendance rate");
+    doc.push(RateHistogram {
+        buckets: summary.rate_histogram,
+    });
+    Ok(())
+}
+
+fn push_student_table(
+    doc: &mut genpdf::Document,
+    report: &AttendanceReport,
+    options: &ExportOptions,
+) -> Result<(), genpdf::error::Error> {
+    push_heading(doc, "Students", 20);
+    doc.push(Break::new(1.0));
+
+    let export = build_table(report, &options.columns);
+    let weights = export
+        .headers
+        .iter()
+        .map(|header| header.column.weight())
+        .collect();
+    let mut table = TableLayout::new(weights);
+    table.set_cell_decorator(genpdf::elements::FrameCellDecorator::new(true, true, true));
+
+    let header_style = Style::new().with_color(BLUE).bold();
+
+    let mut header_row = table.row();
+    for header in &export.headers {
+        header_row.push_element(padded_text(header.label.clone(), header_style));
+    }
+    header_row.push()?;
+    for (i, row) in export.rows.iter().enumerate() {
+        let color = if i % 2 == 0 { BASE01 } else { BASE00 };
+        let row_style = Style::new().with_color(color);
+        let mut table_row = table.row();
+        for value in row {
+            table_row.push_element(padded_text(value.clone(), row_style));
+        }
+        table_row.push()?;
+    }
+    doc.push(table);
+    Ok(())
+}
+
+fn push_student_page(
+    doc: &mut genpdf::Document,
+    report: &AttendanceReport,
+    student: &StudentRecord,
+) -> Result<(), genpdf::error::Error> {
+    push_heading(doc, &format!("{} {}", student.name, student.surname), 18);
+    let mut contact = Paragraph::new(format!("{} \u{00b7} {}", student.id, student.email));
+    contact.set_alignment(Alignment::Center);
+    doc.push(contact.styled(Style::new().with_color(BASE00).with_font_size(10)));
+    doc.push(Break::new(1.0));
+
+    push_subheading(doc, "Score breakdown");
+    let penalty = report.config.late_penalty;
+    let header_style = Style::new().with_color(BLUE).bold();
+    let row_style = Style::new().with_color(BASE01);
+    let mut breakdown = TableLayout::new(vec![2, 1, 1, 1]);
+    breakdown.set_cell_decorator(genpdf::elements::FrameCellDecorator::new(true, true, true));
+    breakdown
+        .row()
+        .element(padded_text("Status", header_style))
+        .element(padded_text("Sessions", header_style))
+        .element(padded_text("Points each", header_style))
+        .element(padded_text("Points", header_style))
+        .push()?;
+    for (label, count, points) in [
+        ("Normal", student.normal, 1.0),
+        ("Late", student.late, penalty),
+        ("Absent", student.absent, 0.0),
+    ] {
+        breakdown
+            .row()
+            .element(padded_text(label, row_style))
+            .element(padded_text(count.to_string(), row_style))
+            .element(padded_text(format!("{points:.2}"), row_style))
+            .element(padded_text(
+                format!("{:.2}", count as f32 * points),
+                row_style,
+            ))
+            .push()?;
+    }
+    breakdown
+        .row()
+        .element(padded_text("Score (capped at total)", header_style))
+        .element(padded_text("", row_style))
+        .element(padded_text("", row_style))
+        .element(padded_text(
+            format!("{:.1}/{:.1}", student.score, report.total_points),
+            header_style,
+        ))
+        .push()?;
+    doc.push(breakdown);
+    doc.push(Break::new(1.0));
+
+    push_subheading(doc, "Session history");
+    let mut history = TableLayout::new(vec![1, 3, 2, 2, 2]);
+    history.set_cell_decorator(genpdf::elements::FrameCellDecorator::new(true, true, true));
+    history
+        .row()
+        .element(padded_text("#", header_style))
+        .element(padded_text("Date", header_style))
+        .element(padded_text("First Join", header_style))
+        .element(padded_text("Min. Late", header_style))
+        .element(padded_text("Status", header_style))
+        .push()?;
+    for (index, session) in student.history.iter().enumerate() {
+        let join = session
+            .first_join
+            .map(|join| join.format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| "\u{2013}".to_string());
+        let late = if session.first_join.is_some() {
+            session.minutes_late.to_string()
+        } else {
+            "\u{2013}".to_string()
+        };
+        let status_style = Style::new().with_color(status_color(session.status)).bold();
+        history
+            .row()
+            .element(padded_text((index + 1).to_string(), row_style))
+            .element(padded_text(
+                session.date.format(DATE_FORMAT).to_string(),
+                row_style,
+            ))
+            .element(padded_text(join, row_style))
+            .element(padded_text(late, row_style))
+            .element(padded_text(status_label(session), status_style))
+            .push()?;
+    }
+    doc.push(history);
+    if student.has_overrides() {
+        doc.push(
+            Paragraph::new("* Set manually; the computed status is shown in brackets.")
+                .styled(row_style),
+        );
+    }
+    Ok(())
+}
+
+fn push_heading(doc: &mut genpdf::Document, text: &str, size: u8) {
+    let mut title = Paragraph::new(text);
+    title.set_alignment(Alignment::Center);
+    doc.push(title.styled(Style::new().with_color(VIOLET).with_font_size(size).bold()));
+}
+
+fn push_subheading(doc: &mut genpdf::Document, text: &str) {
+    doc.push(
+        PaddedElement::new(
+            Paragraph::new(text),
+            genpdf::Margins::trbl(2.0, 0.0, 2.0, 0.0),
+        )
+        .styled(Style::new().with_color(BLUE).with_font_size(13).bold()),
+    );
+}
+
+fn status_label(session: &SessionAttendance) -> String {
+    match session.original {
+        Some(original) => format!("{}* ({original})", session.status),
+        None => session.status.to_string(),
+    }
+}
+
+fn status_color(status: AttendanceStatus) -> Color {
+    match status {
+        AttendanceStatus::Normal => GREEN,
+        AttendanceStatus::Late => YELLOW,
+        AttendanceStatus::Absent => RED,
+    }
+}
+
+fn padded_text(text: impl Into<String>, style: Style) -> impl Element {
+    PaddedElement::new(
+        genpdf::elements::Paragraph::new(text.into()).styled(style),
+        genpdf::Margins::trbl(2.0, 2.0, 2.0, 2.0),
+    )
+}
+
+/// Bar chart of students per attendance-rate decile.
+///
+/// genpdf has no fill primitive, so bars are shaded with closely spaced vertical strokes.
+struct RateHistogram {
+    buckets: [usize; 10],
+}
+
+impl RateHistogram {
+    const HEIGHT: f64 = 70.0;
+    const LABEL_HEIGHT: f64 = 6.0;
+}
+
+impl Element for RateHistogram {
+    fn render(
+        &mut self,
+        context: &Context,
+        area: Area<'_>,
+        style: Style,
+    ) -> Result<RenderResult, genpdf::error::Error> {
+        let width: f64 = area.size().width.into();
+        let available: f64 = area.size().height.into();
+        if available < Self::HEIGHT {
+            return Ok(RenderResult {
+                size: Size::new(0, 0),
+                has_more: true,
+            });
+        }
+
+        let chart_height = Self::HEIGHT - 2.0 * Self::LABEL_HEIGHT;
+        let baseline = Self::LABEL_HEIGHT + chart_height;
+        let slot = width / self.buckets.len() as f64;
+        let max = self.buckets.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let label_style = style.with_color(BASE01).with_font_size(8);
+
+        area.draw_line(
+            vec![Position::new(0.0, baseline), Position::new(width, baseline)],
+            Style::new().with_color(BASE00),
+        );
+        for (index, count) in self.buckets.iter().enumerate() {
+            let left = slot * index as f64 + slot * 0.15;
+            let right = slot * (index + 1) as f64 - slot * 0.15;
+            let top = baseline - chart_height * (*count as f64 / max);
+            let mut x = left;
+            while *count > 0 && x <= right {
+                area.draw_line(
+                    vec![Position::new(x, baseline), Position::new(x, top)],
+                    Style::new().with_color(BLUE),
+                );
+                x += 0.3;
+            }
+            area.print_str(
+                &context.font_cache,
+                Position::new(left, top - Self::LABEL_HEIGHT + 1.0),
+                label_style,
+                count.to_string(),
+            )?;
+            area.print_str(
+                &context.font_cache,
+                Position::new(left, baseline + 1.0),
+                label_style,
+                format!("{}%", index * 10),
+            )?;
+        }
+
+        Ok(RenderResult {
+            size: Size::new(Mm::from(width), Mm::from(Self::HEIGHT)),
+            has_more: false,
+        })
+    }
+}
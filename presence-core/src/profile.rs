@@ -0,0 +1,217 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{AttendanceConfig, ExportOptions};
+use crate::columns;
+use crate::error::{PresenceError, Result};
+use crate::loader::ScanOptions;
+
+/// Analysis and export settings for one course, stored as a TOML or JSON file.
+///
+/// Every field is optional in the file; missing ones take the defaults below.
+/// The GUI and the command line read and write the same files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub class_start: String,
+    pub class_end: String,
+    pub late_minutes: i64,
+    pub absent_minutes: i64,
+    pub total_points: f32,
+    pub late_penalty: f32,
+    /// Percentage of sessions required for eligibility.
+    pub min_attendance: f32,
+    pub course_name: String,
+    pub term: String,
+    /// Export column specification, see `columns::parse_columns`.
+    pub columns: String,
+    pub grade_item: String,
+    pub student_pages: bool,
+    /// Scan subfolders of the source.
+    pub recursive: bool,
+    /// Glob patterns of exports to analyze, see `ScanOptions`.
+    pub include: Vec<String>,
+    /// Glob patterns of exports to skip.
+    pub exclude: Vec<String>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            class_start: "13:30".to_string(),
+            class_end: "15:00".to_string(),
+            late_minutes: 10,
+            absent_minutes: 30,
+            total_points: 10.0,
+            late_penalty: 0.5,
+            min_attendance: 70.0,
+            course_name: String::new(),
+            term: String::new(),
+            columns: columns::DEFAULT_COLUMNS.to_string(),
+            grade_item: "Attendance".to_string(),
+            student_pages: false,
+            recursive: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn to_config(&self) -> AttendanceConfig {
+        AttendanceConfig {
+            class_start: self.class_start.clone(),
+            class_end: self.class_end.clone(),
+            late_minutes: self.late_minutes.to_string(),
+            absent_minutes: self.absent_minutes.to_string(),
+            total_points: self.total_points.to_string(),
+            late_penalty: self.late_penalty.to_string(),
+            min_attendance: self.min_attendance.to_string(),
+        }
+    }
+
+    pub fn to_scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            recursive: self.recursive,
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+        }
+    }
+
+    pub fn to_export_options(&self) -> Result<ExportOptions> {
+        Ok(ExportOptions {
+            course_name: self.course_name.clone(),
+            term: self.term.clone(),
+            student_pages: self.student_pages,
+            columns: columns::parse_columns(&self.columns)?,
+            grade_item: self.grade_item.clone(),
+        })
+    }
+}
+
+/// File name of the profile used when none is chosen explicitly.
+pub const DEFAULT_PROFILE: &str = "default.toml";
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+fn profile_error(
+    path: &Path,
+    source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> PresenceError {
+    PresenceError::Profile {
+        path: path.to_path_buf(),
+        source: source.into(),
+    }
+}
+
+/// Reads a profile. Files ending in `.json` are parsed as JSON, anything else as TOML.
+pub fn load_profile(path: &Path) -> Result<Profile> {
+    let contents = std::fs::read_to_string(path).map_err(|error| PresenceError::io(path, error))?;
+    if is_json(path) {
+        serde_json::from_str(&contents).map_err(|error| profile_error(path, error))
+    } else {
+        toml::from_str(&contents).map_err(|error| profile_error(path, error))
+    }
+}
+
+/// Writes a profile in the format implied by the extension, creating missing folders.
+pub fn save_profile(profile: &Profile, path: &Path) -> Result<()> {
+    let contents = if is_json(path) {
+        serde_json::to_string_pretty(profile).map_err(|error| profile_error(path, error))?
+    } else {
+        toml::to_string_pretty(profile).map_err(|error| profile_error(path, error))?
+    };
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(|error| PresenceError::io(parent, error))?;
+    }
+    std::fs::write(path, contents).map_err(|error| PresenceError::io(path, error))
+}
+
+/// Per-user configuration folder for Presence, e.g. `~/.config/presence` on Linux.
+pub fn config_dir() -> Option<PathBuf> {
+    let env_path = |name: &str| {
+        std::env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+    };
+    let base = if cfg!(windows) {
+        env_path("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_path("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env_path("XDG_CONFIG_HOME").or_else(|| env_path("HOME").map(|home| home.join(".config")))
+    };
+    base.map(|base| base.join("presence"))
+}
+
+/// Location of the default profile in the user configuration folder.
+pub fn default_profile_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(DEFAULT_PROFILE))
+}
+
+/// Loads the default profile, or the built-in defaults when none has been saved yet.
+pub fn load_default_profile() -> Result<Profile> {
+    match default_profile_path() {
+        Some(path) if path.is_file() => load_profile(&path),
+        _ => Ok(Profile::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_profile_fills_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("course.toml");
+        std::fs::write(
+            &path,
+            "class_start = \"09:00\"\nlate_minutes = 5\ncourse_name = \"CS101\"\n",
+        )
+        .unwrap();
+
+        let profile = load_profile(&path).expect("Failed to load profile");
+        assert_eq!(profile.class_start, "09:00");
+        assert_eq!(profile.late_minutes, 5);
+        assert_eq!(profile.course_name, "CS101");
+        assert_eq!(profile.class_end, "15:00");
+
+        let config = profile.to_config();
+        assert_eq!(config.late_minutes, "5");
+        assert_eq!(config.total_points, "10");
+        assert_eq!(config.late_penalty, "0.5");
+
+        std::fs::write(&path, "late_minutes = \"soon\"\n").unwrap();
+        assert!(matches!(
+            load_profile(&path),
+            Err(PresenceError::Profile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_save_profile_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = Profile {
+            course_name: "CS101".to_string(),
+            late_penalty: 0.25,
+            student_pages: true,
+            ..Profile::default()
+        };
+
+        for name in ["course.toml", "nested/course.json"] {
+            let path = dir.path().join(name);
+            save_profile(&profile, &path).expect("Failed to save profile");
+            assert_eq!(load_profile(&path).unwrap(), profile);
+        }
+        let json = std::fs::read_to_string(dir.path().join("nested/course.json")).unwrap();
+        assert!(json.contains("\"course_name\": \"CS101\""));
+    }
+}
@@ -0,0 +1,254 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::StudentRecord;
+use crate::error::{PresenceError, Result};
+use crate::overrides::StatusOverride;
+use crate::profile::{self, Profile};
+
+/// Extension of project files.
+pub const PROJECT_EXTENSION: &str = "presence";
+
+/// Number of entries kept in the recent projects list.
+const MAX_RECENT: usize = 8;
+
+/// Everything needed to resume one course: where the exports live, the
+/// settings used to analyze them, the class roster, manual status overrides
+/// and free-form notes.
+///
+/// Stored as TOML. Sources inside the project's folder are written relative to
+/// it, so a project folder can be handed to a colleague as a whole.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    /// Every folder, export or archive of the source list, in order.
+    pub sources: Vec<PathBuf>,
+    /// Sources kept in the list but left out of the analysis.
+    pub disabled_sources: Vec<PathBuf>,
+    pub notes: String,
+    pub profile: Profile,
+    pub roster: Vec<RosterEntry>,
+    pub overrides: Vec<StatusOverride>,
+}
+
+/// One student known to the project.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RosterEntry {
+    pub id: String,
+    pub name: String,
+    pub surname: String,
+    pub email: String,
+    pub section: String,
+}
+
+impl Project {
+    /// The sources that are analyzed, in order.
+    pub fn enabled_sources(&self) -> Vec<PathBuf> {
+        self.sources
+            .iter()
+            .filter(|source| !self.disabled_sources.contains(source))
+            .cloned()
+            .collect()
+    }
+}
+
+impl From<&StudentRecord> for RosterEntry {
+    fn from(student: &StudentRecord) -> Self {
+        Self {
+            id: student.id.clone(),
+            name: student.name.clone(),
+            surname: student.surname.clone(),
+            email: student.email.clone(),
+            section: student.section.clone(),
+        }
+    }
+}
+
+fn project_error(
+    path: &Path,
+    source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> PresenceError {
+    PresenceError::Project {
+        path: path.to_path_buf(),
+        source: source.into(),
+    }
+}
+
+fn base_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
+
+/// Exports the overrides were recorded for, stored relative like the sources.
+fn override_sources(overrides: &mut [StatusOverride]) -> impl Iterator<Item = &mut PathBuf> {
+    overrides
+        .iter_mut()
+        .filter_map(|entry| entry.source.as_mut())
+}
+
+/// Reads a project file, resolving relative sources against its folder.
+pub fn load_project(path: &Path) -> Result<Project> {
+    let contents = std::fs::read_to_string(path).map_err(|error| PresenceError::io(path, error))?;
+    let mut project: Project =
+        toml::from_str(&contents).map_err(|error| project_error(path, error))?;
+    let base = base_dir(path);
+    for source in project
+        .sources
+        .iter_mut()
+        .chain(&mut project.disabled_sources)
+        .chain(override_sources(&mut project.overrides))
+    {
+        if source.is_relative() {
+            *source = base.join(&*source);
+        }
+    }
+    Ok(project)
+}
+
+/// Writes a project file, storing sources below its folder as relative paths.
+pub fn save_project(project: &Project, path: &Path) -> Result<()> {
+    let base = base_dir(path);
+    let mut stored = project.clone();
+    for source in stored
+        .sources
+        .iter_mut()
+        .chain(&mut stored.disabled_sources)
+        .chain(override_sources(&mut stored.overrides))
+    {
+        if let Ok(relative) = source.strip_prefix(base) {
+            *source = relative.to_path_buf();
+        }
+    }
+    let contents = toml::to_string_pretty(&stored).map_err(|error| project_error(path, error))?;
+    std::fs::write(path, contents).map_err(|error| PresenceError::io(path, error))
+}
+
+/// Display name of a project, taken from its file name.
+pub fn project_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct RecentList {
+    projects: Vec<PathBuf>,
+}
+
+fn recent_list_path() -> Option<PathBuf> {
+    profile::config_dir().map(|dir| dir.join("recent.toml"))
+}
+
+fn read_recent(list: &Path) -> Vec<PathBuf> {
+    std::fs::read_to_string(list)
+        .ok()
+        .and_then(|contents| toml::from_str::<RecentList>(&contents).ok())
+        .map(|recent| recent.projects)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn push_recent(list: &Path, project: &Path) -> Result<()> {
+    let mut projects = read_recent(list);
+    projects.retain(|path| path != project);
+    projects.insert(0, project.to_path_buf());
+    projects.truncate(MAX_RECENT);
+    let contents =
+        toml::to_string(&RecentList { projects }).map_err(|error| project_error(list, error))?;
+    if let Some(parent) = list.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| PresenceError::io(parent, error))?;
+    }
+    std::fs::write(list, contents).map_err(|error| PresenceError::io(list, error))
+}
+
+/// Recently opened or saved projects that still exist, most recent first.
+pub fn recent_projects() -> Vec<PathBuf> {
+    recent_list_path()
+        .map(|list| read_recent(&list))
+        .unwrap_or_default()
+}
+
+/// Moves a project to the top of the recent projects list.
+pub fn remember_project(path: &Path) -> Result<()> {
+    let path = std::fs::canonicalize(path).map_err(|error| PresenceError::io(path, error))?;
+    match recent_list_path() {
+        Some(list) => push_recent(&list, &path),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_round_trip_with_relative_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let inside = dir.path().join("exports");
+        let outside = PathBuf::from("/srv/shared/cs101");
+        let project = Project {
+            sources: vec![inside.clone(), outside.clone()],
+            disabled_sources: vec![outside.clone()],
+            notes: "Week 3 was cancelled.".to_string(),
+            profile: Profile {
+                course_name: "CS101".to_string(),
+                ..Profile::default()
+            },
+            roster: vec![RosterEntry {
+                id: "john".to_string(),
+                name: "John".to_string(),
+                surname: "Doe".to_string(),
+                ..RosterEntry::default()
+            }],
+            overrides: vec![
+                StatusOverride::new(
+                    "john",
+                    chrono::NaiveDate::from_ymd_opt(2023, 10, 27).unwrap(),
+                    Some(inside.join("week-02.csv")),
+                    crate::AttendanceStatus::Normal,
+                    "Was in the room",
+                )
+                .unwrap(),
+            ],
+        };
+
+        let path = dir.path().join("cs101.presence");
+        save_project(&project, &path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("\"exports\""));
+        assert!(contents.contains("\"exports/week-02.csv\""));
+
+        assert_eq!(load_project(&path).unwrap(), project);
+        assert_eq!(project.enabled_sources(), vec![inside.clone()]);
+        assert_eq!(project_name(&path), "cs101");
+
+        std::fs::write(&path, "sources = 3\n").unwrap();
+        assert!(matches!(
+            load_project(&path),
+            Err(PresenceError::Project { .. })
+        ));
+    }
+
+    #[test]
+    fn test_recent_projects_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("config").join("recent.toml");
+        let first = dir.path().join("first.presence");
+        let second = dir.path().join("second.presence");
+        std::fs::write(&first, "").unwrap();
+        std::fs::write(&second, "").unwrap();
+
+        push_recent(&list, &first).unwrap();
+        push_recent(&list, &second).unwrap();
+        push_recent(&list, &first).unwrap();
+        assert_eq!(read_recent(&list), vec![first.clone(), second.clone()]);
+
+        // Deleted projects drop out of the list
+        std::fs::remove_file(&second).unwrap();
+        assert_eq!(read_recent(&list), vec![first]);
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::loader::{self, ScanOptions};
+
+#[cfg(feature = "watch")]
+pub use watcher::SourceWatcher;
+
+/// Size and modification time of every attendance export of a source, used to
+/// tell which files changed between two analyses.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    files: BTreeMap<PathBuf, (u64, Option<SystemTime>)>,
+}
+
+impl Snapshot {
+    /// Records the exports of a directory or single file. A source that cannot
+    /// be read yields an empty snapshot, so its files count as removed.
+    pub fn capture(source: &Path, options: &ScanOptions) -> Self {
+        Self::of_files(loader::scan_source(source, options).unwrap_or_default())
+    }
+
+    /// Records the exports of several sources. Sources that cannot be read
+    /// contribute no files.
+    pub fn capture_all(sources: &[PathBuf], options: &ScanOptions) -> Self {
+        Self::of_files(
+            sources
+                .iter()
+                .flat_map(|source| loader::scan_source(source, options).unwrap_or_default()),
+        )
+    }
+
+    /// Records the given files.
+    pub fn of_files(files: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            files: files
+                .into_iter()
+                .filter_map(|path| {
+                    let metadata = std::fs::metadata(&path).ok()?;
+                    Some((path, (metadata.len(), metadata.modified().ok())))
+                })
+                .collect(),
+        }
+    }
+
+    /// Files added, changed or removed since an earlier snapshot.
+    pub fn changes_since(&self, earlier: &Snapshot) -> SourceChanges {
+        let mut changes = SourceChanges::default();
+        for (path, stamp) in &self.files {
+            match earlier.files.get(path) {
+                None => changes.added.push(path.clone()),
+                Some(previous) if previous != stamp => changes.changed.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = earlier
+            .files
+            .keys()
+            .filter(|path| !self.files.contains_key(*path))
+            .cloned()
+            .collect();
+        changes
+    }
+}
+
+/// Difference between two snapshots of a source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceChanges {
+    pub added: Vec<PathBuf>,
+    pub changed: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+}
+
+impl SourceChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Summary such as `1 added (week-05.csv), 1 removed (week-01.csv)`.
+impl fmt::Display for SourceChanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups = [
+            ("added", &self.added),
+            ("changed", &self.changed),
+            ("removed", &self.removed),
+        ];
+        let mut first = true;
+        for (label, paths) in groups {
+            if paths.is_empty() {
+                continue;
+            }
+            let names: Vec<String> = paths
+                .iter()
+                .map(|path| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path.display().to_string())
+                })
+                .collect();
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} {label} ({})", paths.len(), names.join(", "))?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "watch")]
+mod watcher {
+    use std::path::Path;
+    use std::time::Duration;
+
+    use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+    use notify_debouncer_mini::{DebounceEventResult, Debouncer, new_debouncer};
+
+    use crate::error::{PresenceError, Result};
+
+    /// Quiet period after the last file-system event before `on_change` runs,
+    /// so an export that is still being copied is reported once.
+    const DEBOUNCE: Duration = Duration::from_millis(750);
+
+    /// Calls a function whenever something changes in an attendance source.
+    /// Watching stops when the watcher is dropped.
+    pub struct SourceWatcher {
+        _debouncer: Debouncer<RecommendedWatcher>,
+    }
+
+    impl SourceWatcher {
+        /// Watches a directory, including subfolders when `recursive` is set,
+        /// or the folder of a single export. `on_change` runs on a background
+        /// thread; compare [`super::Snapshot`]s to find out what changed.
+        pub fn new(
+            source: &Path,
+            recursive: bool,
+            mut on_change: impl FnMut() + Send + 'static,
+        ) -> Result<Self> {
+            let watched = if source.is_file() {
+                source.parent().unwrap_or(Path::new("."))
+            } else {
+                source
+            };
+            let watch_error = |error| PresenceError::Watch {
+                path: watched.to_path_buf(),
+                source: Box::new(error),
+            };
+            let mut debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+                if result.is_ok_and(|events| !events.is_empty()) {
+                    on_change();
+                }
+            })
+            .map_err(watch_error)?;
+            debouncer
+                .watcher()
+                .watch(
+                    watched,
+                    if recursive && source.is_dir() {
+                        RecursiveMode::Recursive
+                    } else {
+                        RecursiveMode::NonRecursive
+                    },
+                )
+                .map_err(watch_error)?;
+            Ok(Self {
+                _debouncer: debouncer,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("week-01.csv");
+        let second = dir.path().join("week-02.csv");
+        std::fs::write(&first, "Name,First Join\n").unwrap();
+        std::fs::write(&second, "Name,First Join\n").unwrap();
+        let options = ScanOptions::default();
+        let before = Snapshot::capture(dir.path(), &options);
+        assert!(
+            Snapshot::capture(dir.path(), &options)
+                .changes_since(&before)
+                .is_empty()
+        );
+
+        std::fs::remove_file(&first).unwrap();
+        std::fs::write(&second, "Name,First Join\nJohn Doe,\n").unwrap();
+        let third = dir.path().join("week-03.csv");
+        std::fs::write(&third, "Name,First Join\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let changes = Snapshot::capture(dir.path(), &options).changes_since(&before);
+        assert_eq!(
+            changes,
+            SourceChanges {
+                added: vec![third],
+                changed: vec![second],
+                removed: vec![first],
+            }
+        );
+        assert_eq!(
+            changes.to_string(),
+            "1 added (week-03.csv), 1 changed (week-02.csv), 1 removed (week-01.csv)"
+        );
+    }
+}
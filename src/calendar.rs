@@ -0,0 +1,360 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::canvas::{self, event};
+use iced::{Color, Pixels, Point, Rectangle, Renderer, Size, Theme, mouse};
+use presence_core::{AttendanceReport, StudentRecord};
+
+use crate::messages::Message;
+use crate::style;
+
+const CELL: f32 = 14.0;
+const GAP: f32 = 3.0;
+const WEEKDAY_WIDTH: f32 = 32.0;
+const MONTH_HEIGHT: f32 = 18.0;
+const HOVER_HEIGHT: f32 = 22.0;
+
+/// What a session day of the calendar shows.
+struct Day {
+    /// Sessions held that day, in session order.
+    sessions: Vec<usize>,
+    /// Colour of each session; a day with several sessions is split into
+    /// one strip per session.
+    colors: Vec<Color>,
+    label: String,
+}
+
+impl Day {
+    /// Session opened by a click: the first one, or the one after the
+    /// selected session when it is on this day, so repeated clicks step
+    /// through every session of the day.
+    fn next_session(&self, selected: Option<usize>) -> usize {
+        match selected.and_then(|selected| self.sessions.iter().position(|&s| s == selected)) {
+            Some(position) => self.sessions[(position + 1) % self.sessions.len()],
+            None => self.sessions[0],
+        }
+    }
+}
+
+/// GitHub-style calendar of the term: one column per week, one row per
+/// weekday, one cell per day. Session days are coloured by the class
+/// attendance rate, or by the status of a single student; clicking one opens
+/// its session.
+pub struct CalendarHeatmap {
+    first_monday: NaiveDate,
+    weeks: usize,
+    days: BTreeMap<NaiveDate, Day>,
+    selected: Option<usize>,
+    selected_date: Option<NaiveDate>,
+}
+
+impl CalendarHeatmap {
+    /// `rates` is the attendance rate of each session, aligned with
+    /// `report.session_dates`; `student` switches the colours to that
+    /// student's statuses.
+    pub fn new(
+        report: &AttendanceReport,
+        rates: &[f32],
+        student: Option<&StudentRecord>,
+        selected_session: Option<usize>,
+    ) -> Self {
+        let (first, last) = report.date_range().unwrap_or_default();
+        let first_monday = first - Duration::days(first.weekday().num_days_from_monday().into());
+        let weeks = ((last - first_monday).num_days() / 7 + 1) as usize;
+
+        let mut sessions: BTreeMap<NaiveDate, Vec<usize>> = BTreeMap::new();
+        for (index, date) in report.session_dates.iter().enumerate() {
+            sessions.entry(*date).or_default().push(index);
+        }
+        let days = sessions
+            .into_iter()
+            .map(|(date, sessions)| {
+                let (colors, labels): (Vec<Color>, Vec<String>) = sessions
+                    .iter()
+                    .map(|&session| {
+                        match student.and_then(|student| student.history.get(session)) {
+                            Some(attendance) => (
+                                style::status_color(attendance.status),
+                                attendance.status.to_string(),
+                            ),
+                            None => {
+                                let rate = rates.get(session).copied().unwrap_or(0.0);
+                                (rate_color(rate), format!("{:.1}%", rate * 100.0))
+                            }
+                        }
+                    })
+                    .unzip();
+                let suffix = if student.is_some() { "" } else { " present" };
+                let label = match labels.as_slice() {
+                    [label] => format!("{label}{suffix}"),
+                    labels => format!(
+                        "{} sessions: {}{suffix}, click to step through",
+                        labels.len(),
+                        labels.join(", ")
+                    ),
+                };
+                let day = Day {
+                    sessions,
+                    colors,
+                    label,
+                };
+                (date, day)
+            })
+            .collect();
+
+        Self {
+            first_monday,
+            weeks,
+            days,
+            selected: selected_session,
+            selected_date: selected_session
+                .and_then(|index| report.session_dates.get(index).copied()),
+        }
+    }
+
+    /// Size the calendar needs, so it can sit in a scrollable.
+    pub fn size(&self) -> Size {
+        Size::new(
+            WEEKDAY_WIDTH + self.weeks as f32 * (CELL + GAP),
+            MONTH_HEIGHT + 7.0 * (CELL + GAP) + HOVER_HEIGHT,
+        )
+    }
+
+    fn cell_origin(&self, date: NaiveDate) -> Point {
+        let days = (date - self.first_monday).num_days();
+        Point::new(
+            WEEKDAY_WIDTH + (days / 7) as f32 * (CELL + GAP),
+            MONTH_HEIGHT + (days % 7) as f32 * (CELL + GAP),
+        )
+    }
+
+    fn date_at(&self, position: Point) -> Option<NaiveDate> {
+        let column = (position.x - WEEKDAY_WIDTH) / (CELL + GAP);
+        let row = (position.y - MONTH_HEIGHT) / (CELL + GAP);
+        if !(0.0..self.weeks as f32).contains(&column) || !(0.0..7.0).contains(&row) {
+            return None;
+        }
+        Some(self.first_monday + Duration::days(column as i64 * 7 + row as i64))
+    }
+
+    fn session_at(&self, cursor: mouse::Cursor, bounds: Rectangle) -> Option<(NaiveDate, &Day)> {
+        let date = self.date_at(cursor.position_in(bounds)?)?;
+        self.days.get(&date).map(|day| (date, day))
+    }
+}
+
+/// Red for an empty session through yellow to green for full attendance.
+fn rate_color(rate: f32) -> Color {
+    let mix = |from: Color, to: Color, amount: f32| Color {
+        r: from.r + (to.r - from.r) * amount,
+        g: from.g + (to.g - from.g) * amount,
+        b: from.b + (to.b - from.b) * amount,
+        a: 1.0,
+    };
+    let rate = rate.clamp(0.0, 1.0);
+    if rate < 0.5 {
+        mix(style::CHART_RED, style::CHART_YELLOW, rate * 2.0)
+    } else {
+        mix(style::CHART_YELLOW, style::CHART_GREEN, rate * 2.0 - 1.0)
+    }
+}
+
+impl canvas::Program<Message> for CalendarHeatmap {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                match self.session_at(cursor, bounds) {
+                    Some((_, day)) => (
+                        event::Status::Captured,
+                        Some(Message::SelectSession(day.next_session(self.selected))),
+                    ),
+                    None => (event::Status::Ignored, None),
+                }
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let text = |content: String, position: Point, color: Color| canvas::Text {
+            content,
+            position,
+            color,
+            size: Pixels(11.0),
+            vertical_alignment: Vertical::Center,
+            ..canvas::Text::default()
+        };
+
+        for (row, weekday) in [(0, "Mon"), (2, "Wed"), (4, "Fri")] {
+            frame.fill_text(text(
+                weekday.to_string(),
+                Point::new(0.0, MONTH_HEIGHT + row as f32 * (CELL + GAP) + CELL / 2.0),
+                style::BASE00,
+            ));
+        }
+
+        let mut month = None;
+        for week in 0..self.weeks {
+            let monday = self.first_monday + Duration::weeks(week as i64);
+            if month != Some(monday.month()) {
+                month = Some(monday.month());
+                frame.fill_text(text(
+                    monday.format("%b").to_string(),
+                    Point::new(self.cell_origin(monday).x, MONTH_HEIGHT / 2.0),
+                    style::BASE00,
+                ));
+            }
+            for weekday in 0..7 {
+                let date = monday + Duration::days(weekday);
+                let origin = self.cell_origin(date);
+                match self.days.get(&date) {
+                    Some(day) => {
+                        let width = CELL / day.colors.len() as f32;
+                        for (strip, color) in day.colors.iter().enumerate() {
+                            frame.fill_rectangle(
+                                Point::new(origin.x + strip as f32 * width, origin.y),
+                                Size::new(width, CELL),
+                                *color,
+                            );
+                        }
+                    }
+                    None => frame.fill_rectangle(origin, Size::new(CELL, CELL), style::BASE02),
+                }
+                if self.selected_date == Some(date) {
+                    frame.stroke(
+                        &canvas::Path::rectangle(origin, Size::new(CELL, CELL)),
+                        canvas::Stroke::default()
+                            .with_color(style::CYAN)
+                            .with_width(2.0),
+                    );
+                }
+            }
+        }
+
+        if let Some((date, day)) = self.session_at(cursor, bounds) {
+            frame.stroke(
+                &canvas::Path::rectangle(self.cell_origin(date), Size::new(CELL, CELL)),
+                canvas::Stroke::default()
+                    .with_color(style::BASE1)
+                    .with_width(2.0),
+            );
+            frame.fill_text(canvas::Text {
+                horizontal_alignment: Horizontal::Left,
+                ..text(
+                    format!("{}: {}", date.format("%a %Y-%m-%d"), day.label),
+                    Point::new(WEEKDAY_WIDTH, bounds.height - HOVER_HEIGHT / 2.0),
+                    style::BASE1,
+                )
+            });
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if self.session_at(cursor, bounds).is_some() {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_cells_map_back_to_dates() {
+        let report = AttendanceReport {
+            students: Vec::new(),
+            sessions: 2,
+            total_points: 2.0,
+            // A Wednesday and the Tuesday two weeks later
+            session_dates: vec![
+                NaiveDate::from_ymd_opt(2023, 10, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 11, 7).unwrap(),
+            ],
+            session_sources: Vec::new(),
+            config: presence_core::parse_config(
+                presence_core::profile::Profile::default().to_config(),
+            )
+            .unwrap(),
+        };
+        let calendar = CalendarHeatmap::new(&report, &[1.0, 0.5], None, Some(1));
+        assert_eq!(
+            calendar.first_monday,
+            NaiveDate::from_ymd_opt(2023, 10, 23).unwrap()
+        );
+        assert_eq!(calendar.weeks, 3);
+        assert_eq!(calendar.selected_date, report.session_dates.get(1).copied());
+
+        for (index, date) in report.session_dates.iter().enumerate() {
+            let origin = calendar.cell_origin(*date);
+            let inside = Point::new(origin.x + CELL / 2.0, origin.y + CELL / 2.0);
+            assert_eq!(calendar.date_at(inside), Some(*date));
+            assert_eq!(calendar.days[date].sessions, vec![index]);
+        }
+        assert_eq!(calendar.date_at(Point::new(0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_day_with_several_sessions() {
+        let date = NaiveDate::from_ymd_opt(2023, 10, 25).unwrap();
+        let mut report = presence_core::generate_report(
+            [
+                "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:45:00 PM\"",
+                "Name,Email,First Join\nJane Roe,jane@example.com,\"10/25/23, 1:30:00 PM\"",
+                "Name,Email,First Join\nJohn Doe,john@example.com,\"10/26/23, 1:30:00 PM\"",
+            ]
+            .iter()
+            .map(|csv| presence_core::parse_participants(csv.as_bytes(), "csv").unwrap())
+            .collect(),
+            presence_core::profile::Profile::default().to_config(),
+        )
+        .unwrap();
+        report.students.sort_by(|a, b| a.email.cmp(&b.email));
+        let john = &report.students[1];
+
+        let calendar = CalendarHeatmap::new(&report, &[0.5, 0.5, 0.5], Some(john), None);
+        let day = &calendar.days[&date];
+        assert_eq!(day.sessions, vec![0, 1]);
+        // One strip per session: late in the first, absent in the second
+        assert_eq!(
+            day.colors,
+            vec![
+                style::status_color(presence_core::AttendanceStatus::Late),
+                style::status_color(presence_core::AttendanceStatus::Absent),
+            ]
+        );
+        assert!(day.label.starts_with("2 sessions: Late, Absent"));
+
+        // Clicks step through the day's sessions and wrap around
+        assert_eq!(day.next_session(None), 0);
+        assert_eq!(day.next_session(Some(0)), 1);
+        assert_eq!(day.next_session(Some(1)), 0);
+        assert_eq!(day.next_session(Some(2)), 0);
+    }
+}
@@ -0,0 +1,549 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use presence_core::charts;
+use presence_core::export;
+use presence_core::profile;
+use presence_core::watch::{Snapshot, SourceWatcher};
+use presence_core::{
+    AttendanceConfig, ChartFormat, ChartSize, ChartType, ExportOptions, ReportFormat, ScanOptions,
+    SessionCache, load_attendance_files, scan_source,
+};
+
+use crate::state::user_message;
+
+const USAGE: &str = "\
+Usage: presence analyze <path>... [options]
+
+Analyzes attendance exports without opening a window. <path> is a folder of
+CSV/XLSX exports or a single export. When several paths are given, the reports
+for each are written to a subfolder of --out named after the path; paths with
+the same name are prefixed with their parent folder's name.
+
+Options:
+  -c, --config <file>     Course profile (TOML or JSON) with class times,
+                          thresholds and export settings (default: the
+                          default profile saved from the GUI, if any)
+  -f, --format <list>     Comma-separated formats: csv, txt, pdf, moodle,
+                          canvas, blackboard (default: csv)
+  -o, --out <dir>         Output folder (default: current directory)
+      --charts <list>     Also export charts: pie, timeline (one per
+                          student), trend, scores, lateness, or all
+      --chart-format <f>  svg or png (default: svg)
+      --chart-size <WxH>  Chart size in pixels (default: 1200x675)
+  -r, --recursive         Also scan subfolders of <path>
+      --include <globs>   Only analyze exports matching these comma-separated
+                          patterns, relative to <path>, e.g. 'week-*/*.csv'
+      --exclude <globs>   Skip exports matching these patterns, e.g.
+                          '*_summary.csv'
+  -w, --watch             Keep running and re-export whenever exports are
+                          added, changed or removed
+  -h, --help              Show this help
+
+Run `presence` without arguments to start the graphical interface.";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Analyze(AnalyzeArgs),
+    Help,
+    Version,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeArgs {
+    pub sources: Vec<PathBuf>,
+    pub config: Option<PathBuf>,
+    pub formats: Vec<ReportFormat>,
+    pub out: PathBuf,
+    pub watch: bool,
+    pub recursive: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub charts: Vec<ChartType>,
+    pub chart_format: ChartFormat,
+    pub chart_size: ChartSize,
+}
+
+/// Settings shared by every source of one run.
+struct RunSettings {
+    config: AttendanceConfig,
+    options: ExportOptions,
+    scan: ScanOptions,
+    /// Output folder of each source, in the order of `AnalyzeArgs::sources`.
+    folders: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Failed(String),
+}
+
+/// Whether the arguments ask for the command-line interface rather than the GUI.
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    matches!(
+        args.first().map(String::as_str),
+        Some("analyze" | "help" | "--help" | "-h" | "--version" | "-V")
+    )
+}
+
+/// Runs the command line and maps failures to exit codes: 1 when an analysis
+/// or export fails, 2 for invalid arguments.
+pub fn run(args: &[String]) -> ExitCode {
+    let result = parse_args(args).map_err(CliError::Usage).and_then(execute);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<CliCommand, String> {
+    let mut args = args.iter();
+    match args.next().map(String::as_str) {
+        Some("analyze") => {}
+        Some("help" | "--help" | "-h") => return Ok(CliCommand::Help),
+        Some("--version" | "-V") => return Ok(CliCommand::Version),
+        Some(other) => return Err(format!("Unknown command '{other}'.")),
+        None => return Err("Missing command.".to_string()),
+    }
+
+    let mut parsed = AnalyzeArgs {
+        sources: Vec::new(),
+        config: None,
+        formats: Vec::new(),
+        out: PathBuf::from("."),
+        watch: false,
+        recursive: false,
+        include: Vec::new(),
+        exclude: Vec::new(),
+        charts: Vec::new(),
+        chart_format: ChartFormat::Svg,
+        chart_size: ChartSize::default(),
+    };
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| format!("Missing value for {name}."))
+        };
+        match flag {
+            "-h" | "--help" => return Ok(CliCommand::Help),
+            "-c" | "--config" => parsed.config = Some(PathBuf::from(value("--config")?)),
+            "-o" | "--out" => parsed.out = PathBuf::from(value("--out")?),
+            "-w" | "--watch" => parsed.watch = true,
+            "-r" | "--recursive" => parsed.recursive = true,
+            "--include" => parsed
+                .include
+                .extend(ScanOptions::split_patterns(&value("--include")?)),
+            "--exclude" => parsed
+                .exclude
+                .extend(ScanOptions::split_patterns(&value("--exclude")?)),
+            "-f" | "--format" => {
+                for key in value("--format")?
+                    .split(',')
+                    .filter(|key| !key.trim().is_empty())
+                {
+                    let format = key
+                        .parse::<ReportFormat>()
+                        .map_err(|error| error.to_string())?;
+                    if !parsed.formats.contains(&format) {
+                        parsed.formats.push(format);
+                    }
+                }
+            }
+            "--charts" => {
+                for key in value("--charts")?
+                    .split(',')
+                    .filter(|key| !key.trim().is_empty())
+                {
+                    let charts = if key.trim().eq_ignore_ascii_case("all") {
+                        ChartType::ALL.to_vec()
+                    } else {
+                        vec![
+                            key.parse::<ChartType>()
+                                .map_err(|error| error.to_string())?,
+                        ]
+                    };
+                    for chart in charts {
+                        if !parsed.charts.contains(&chart) {
+                            parsed.charts.push(chart);
+                        }
+                    }
+                }
+            }
+            "--chart-format" => {
+                parsed.chart_format = value("--chart-format")?
+                    .parse::<ChartFormat>()
+                    .map_err(|error| error.to_string())?
+            }
+            "--chart-size" => {
+                parsed.chart_size = value("--chart-size")?
+                    .parse::<ChartSize>()
+                    .map_err(|error| error.to_string())?
+            }
+            _ if flag.starts_with('-') => return Err(format!("Unknown option '{flag}'.")),
+            _ => parsed.sources.push(PathBuf::from(arg)),
+        }
+    }
+
+    if parsed.sources.is_empty() {
+        return Err("Missing attendance path.".to_string());
+    }
+    if parsed.formats.is_empty() {
+        parsed.formats.push(ReportFormat::Csv);
+    }
+    Ok(CliCommand::Analyze(parsed))
+}
+
+fn execute(command: CliCommand) -> Result<(), CliError> {
+    let args = match command {
+        CliCommand::Analyze(args) => args,
+        CliCommand::Help => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+        CliCommand::Version => {
+            println!("presence {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+    };
+
+    let profile = match &args.config {
+        Some(path) => {
+            profile::load_profile(path).map_err(|error| CliError::Failed(user_message(&error)))?
+        }
+        None => profile::load_default_profile()
+            .map_err(|error| CliError::Failed(user_message(&error)))?,
+    };
+    let options = profile
+        .to_export_options()
+        .map_err(|error| CliError::Failed(user_message(&error)))?;
+    let mut scan = profile.to_scan_options();
+    scan.recursive |= args.recursive;
+    scan.include.extend(args.include.iter().cloned());
+    scan.exclude.extend(args.exclude.iter().cloned());
+    let settings = RunSettings {
+        config: profile.to_config(),
+        options,
+        scan,
+        folders: output_folders(&args),
+    };
+
+    let mut caches = vec![SessionCache::new(); args.sources.len()];
+    let snapshots: Vec<Snapshot> = args
+        .sources
+        .iter()
+        .map(|source| Snapshot::capture(source, &settings.scan))
+        .collect();
+    let mut failures = 0usize;
+    for (index, cache) in caches.iter_mut().enumerate() {
+        if !run_source(&args, index, &settings, cache) {
+            failures += 1;
+        }
+    }
+    if args.watch {
+        return watch_sources(&args, &settings, caches, snapshots);
+    }
+
+    match failures {
+        0 => Ok(()),
+        _ => Err(CliError::Failed(format!(
+            "{failures} of {} sources failed.",
+            args.sources.len()
+        ))),
+    }
+}
+
+/// Where the reports of each source go: `--out` itself, or a subfolder per
+/// source named after it when several are analyzed. Sources that share a name,
+/// e.g. `a/week1` and `b/week1`, are prefixed with their parent folder's name,
+/// and numbered if that still collides.
+fn output_folders(args: &AnalyzeArgs) -> Vec<PathBuf> {
+    if args.sources.len() <= 1 {
+        return vec![args.out.clone(); args.sources.len()];
+    }
+    // `.` and `..` have no name of their own, so name them after the folder they point to
+    let resolved: Vec<PathBuf> = args
+        .sources
+        .iter()
+        .map(|source| {
+            std::fs::canonicalize(source)
+                .or_else(|_| std::path::absolute(source))
+                .unwrap_or_else(|_| source.clone())
+        })
+        .collect();
+    let stem = |path: &Path| {
+        path.file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "report".to_string())
+    };
+    // Compared case-insensitively, as on Windows and macOS file systems
+    let shared = |names: &[String], name: &str| {
+        names
+            .iter()
+            .filter(|other| other.eq_ignore_ascii_case(name))
+            .count()
+            > 1
+    };
+    let stems: Vec<String> = resolved.iter().map(|path| stem(path)).collect();
+    let names: Vec<String> = resolved
+        .iter()
+        .zip(&stems)
+        .map(|(path, name)| match path.parent() {
+            Some(parent) if shared(&stems, name) && parent.file_name().is_some() => {
+                format!("{}-{name}", stem(parent))
+            }
+            _ => name.clone(),
+        })
+        .collect();
+    let mut used: Vec<String> = Vec::new();
+    for name in names {
+        let mut unique = name.clone();
+        let mut number = 1;
+        while used.iter().any(|other| other.eq_ignore_ascii_case(&unique)) {
+            number += 1;
+            unique = format!("{name}-{number}");
+        }
+        used.push(unique);
+    }
+    used.iter().map(|name| args.out.join(name)).collect()
+}
+
+/// Analyzes and exports one source, reporting the outcome. Returns whether it succeeded.
+fn run_source(
+    args: &AnalyzeArgs,
+    index: usize,
+    settings: &RunSettings,
+    cache: &mut SessionCache,
+) -> bool {
+    let source = &args.sources[index];
+    let folder = &settings.folders[index];
+    let result = scan_source(source, &settings.scan)
+        .and_then(|files| load_attendance_files(&files, settings.config.clone(), cache))
+        .and_then(|report| {
+            eprintln!(
+                "{}: {} students across {} sessions",
+                source.display(),
+                report.students.len(),
+                report.sessions
+            );
+            let mut paths =
+                export::export_formats(&report, &args.formats, &settings.options, folder)?;
+            if !args.charts.is_empty() {
+                paths.extend(charts::export_charts(
+                    &report,
+                    &args.charts,
+                    args.chart_format,
+                    args.chart_size,
+                    &settings.options,
+                    folder,
+                )?);
+            }
+            Ok(paths)
+        });
+    match result {
+        Ok(paths) => {
+            for path in paths {
+                println!("{}", path.display());
+            }
+            true
+        }
+        Err(error) => {
+            eprintln!("{}: {}", source.display(), user_message(&error));
+            false
+        }
+    }
+}
+
+/// Re-exports a source whenever its attendance files change. Runs until the
+/// process is interrupted.
+fn watch_sources(
+    args: &AnalyzeArgs,
+    settings: &RunSettings,
+    mut caches: Vec<SessionCache>,
+    mut snapshots: Vec<Snapshot>,
+) -> Result<(), CliError> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut watchers = Vec::new();
+    for (index, source) in args.sources.iter().enumerate() {
+        let sender = sender.clone();
+        let watcher = SourceWatcher::new(source, settings.scan.recursive, move || {
+            let _ = sender.send(index);
+        })
+        .map_err(|error| CliError::Failed(user_message(&error)))?;
+        watchers.push(watcher);
+    }
+    eprintln!("Watching for changes. Press Ctrl+C to stop.");
+
+    while let Ok(index) = receiver.recv() {
+        let source = &args.sources[index];
+        let snapshot = Snapshot::capture(source, &settings.scan);
+        let changes = snapshot.changes_since(&snapshots[index]);
+        if changes.is_empty() {
+            continue;
+        }
+        snapshots[index] = snapshot;
+        eprintln!("{}: {changes}", source.display());
+        run_source(args, index, settings, &mut caches[index]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let command = parse_args(&args(&[
+            "analyze",
+            "week-01",
+            "--config",
+            "course.toml",
+            "--format=csv,PDF,csv",
+            "-o",
+            "reports",
+            "--watch",
+            "-r",
+            "--exclude=*_summary.csv",
+            "--charts",
+            "trend,pie,trend",
+            "--chart-format=PNG",
+            "--chart-size",
+            "800x600",
+        ]))
+        .unwrap();
+        assert_eq!(
+            command,
+            CliCommand::Analyze(AnalyzeArgs {
+                sources: vec![PathBuf::from("week-01")],
+                config: Some(PathBuf::from("course.toml")),
+                formats: vec![ReportFormat::Csv, ReportFormat::Pdf],
+                out: PathBuf::from("reports"),
+                watch: true,
+                recursive: true,
+                include: Vec::new(),
+                exclude: vec!["*_summary.csv".to_string()],
+                charts: vec![ChartType::Trend, ChartType::Pie],
+                chart_format: ChartFormat::Png,
+                chart_size: ChartSize {
+                    width: 800,
+                    height: 600,
+                },
+            })
+        );
+
+        assert!(is_cli_invocation(&args(&["analyze"])));
+        assert!(!is_cli_invocation(&args(&[])));
+        assert!(parse_args(&args(&["analyze"])).is_err());
+        assert!(parse_args(&args(&["analyze", "x", "--format", "docx"])).is_err());
+        assert!(parse_args(&args(&["analyze", "x", "--out"])).is_err());
+        assert!(parse_args(&args(&["analyze", "x", "--verbose"])).is_err());
+        assert!(parse_args(&args(&["analyze", "x", "--charts", "radar"])).is_err());
+        assert!(parse_args(&args(&["analyze", "x", "--chart-size", "big"])).is_err());
+        assert_eq!(
+            parse_args(&args(&["analyze", "-h"])).unwrap(),
+            CliCommand::Help
+        );
+    }
+
+    #[test]
+    fn test_execute_writes_reports_per_source() {
+        let dir = tempfile::tempdir().unwrap();
+        for section in ["section-a", "section-b"] {
+            let folder = dir.path().join(section);
+            std::fs::create_dir(&folder).unwrap();
+            std::fs::write(
+                folder.join("session1.csv"),
+                "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\"",
+            )
+            .unwrap();
+        }
+        let config = dir.path().join("course.toml");
+        std::fs::write(&config, "course_name = \"CS101\"\n").unwrap();
+        let out = dir.path().join("reports");
+
+        let command = CliCommand::Analyze(AnalyzeArgs {
+            sources: vec![dir.path().join("section-a"), dir.path().join("section-b")],
+            config: Some(config),
+            formats: vec![ReportFormat::Csv, ReportFormat::Txt],
+            out: out.clone(),
+            watch: false,
+            recursive: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            charts: vec![ChartType::Trend],
+            chart_format: ChartFormat::Svg,
+            chart_size: ChartSize::default(),
+        });
+        execute(command).expect("CLI run failed");
+        let config = dir.path().join("course.toml");
+
+        assert!(out.join("section-a").join("cs101-attendance.csv").is_file());
+        assert!(out.join("section-b").join("cs101-attendance.txt").is_file());
+        assert!(
+            out.join("section-b")
+                .join("cs101-attendance-trend.svg")
+                .is_file()
+        );
+
+        let command = CliCommand::Analyze(AnalyzeArgs {
+            sources: vec![dir.path().join("missing")],
+            config: Some(config),
+            formats: vec![ReportFormat::Csv],
+            out,
+            watch: false,
+            recursive: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            charts: Vec::new(),
+            chart_format: ChartFormat::Svg,
+            chart_size: ChartSize::default(),
+        });
+        assert!(matches!(execute(command), Err(CliError::Failed(_))));
+    }
+
+    #[test]
+    fn test_output_folders_are_unique() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = PathBuf::from("reports");
+        let mut args = match parse_args(&args(&["analyze", "x", "-o", "reports"])).unwrap() {
+            CliCommand::Analyze(args) => args,
+            other => panic!("unexpected command: {other:?}"),
+        };
+        assert_eq!(output_folders(&args), vec![out.clone()]);
+
+        args.sources = ["a/week1", "b/week1", "b/Week1.zip", "a/week2", "a/.."]
+            .iter()
+            .map(|source| dir.path().join(source))
+            .collect();
+        std::fs::create_dir_all(dir.path().join("a")).unwrap();
+        let folder_name = dir.path().file_name().unwrap().to_string_lossy();
+        assert_eq!(
+            output_folders(&args),
+            vec![
+                out.join("a-week1"),
+                out.join("b-week1"),
+                out.join("b-Week1-2"),
+                out.join("week2"),
+                out.join(&*folder_name),
+            ]
+        );
+    }
+}
@@ -0,0 +1,448 @@
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::canvas::{self, Canvas};
+use iced::widget::{Row, Space, button, column, container, row, scrollable, text};
+use iced::{
+    Alignment, Color, Element, Length, Pixels, Point, Rectangle, Renderer, Size, Theme, mouse,
+    theme,
+};
+use presence_core::charts::{self, Chart, ChartType, Guide, Mark, Plot};
+use presence_core::{AttendanceReport, summarize};
+
+use crate::calendar::CalendarHeatmap;
+use crate::messages::Message;
+use crate::sessions::session_detail_view;
+use crate::state::AppState;
+use crate::style;
+
+const AXIS_HEIGHT: f32 = 18.0;
+const LABEL_HEIGHT: f32 = 14.0;
+const PERCENT_AXIS_WIDTH: f32 = 40.0;
+
+/// Class-wide overview: headline numbers, the attendance rate of every
+/// session, a calendar of the term and how scores and join times are spread.
+/// Built from the current report, so it follows every re-analysis and
+/// override.
+pub fn dashboard_view<'a>(state: &AppState, report: &'a AttendanceReport) -> Element<'a, Message> {
+    let summary = summarize(report);
+    let percent = |rate: f32| format!("{:.1}%", rate * 100.0);
+    let headline = [
+        ("STUDENTS", summary.students.to_string()),
+        ("SESSIONS", summary.sessions.to_string()),
+        ("MEAN ATTENDANCE", percent(summary.mean_rate)),
+        ("AT RISK", summary.at_risk.to_string()),
+        (
+            "INELIGIBLE",
+            (summary.students - summary.eligible).to_string(),
+        ),
+        (
+            "MEAN SCORE",
+            format!("{:.1}/{:.1}", summary.mean_score, report.total_points),
+        ),
+    ]
+    .into_iter()
+    .fold(Row::new().spacing(12), |cards, (label, value)| {
+        cards.push(
+            container(
+                column![
+                    text(value).size(24).style(style::CYAN),
+                    text(label).size(12).style(style::BASE00),
+                ]
+                .spacing(4),
+            )
+            .style(theme::Container::Custom(Box::new(style::BorderedPanel)))
+            .padding(12)
+            .width(Length::Fill),
+        )
+    });
+
+    let trend = charts::session_trend(report, &summary);
+    let scores = charts::score_histogram(report, &summary);
+    let lateness = charts::lateness_histogram(&summary);
+
+    let student = state
+        .selected_student
+        .and_then(|index| report.students.get(index));
+    let heatmap = CalendarHeatmap::new(
+        report,
+        &summary.session_rates,
+        student,
+        state.selected_session,
+    );
+    let calendar_size = heatmap.size();
+    let calendar_title = match student {
+        Some(student) => row![
+            text(format!(
+                "CALENDAR \u{00b7} {} {}",
+                student.name, student.surname
+            ))
+            .size(14)
+            .style(style::BASE1),
+            Space::with_width(Length::Fill),
+            button(text("SHOW CLASS").size(12))
+                .on_press(Message::ClearStudentSelection)
+                .style(theme::Button::Custom(Box::new(style::Button)))
+                .padding([4, 8]),
+        ],
+        None => row![text("CALENDAR").size(14).style(style::BASE1)],
+    }
+    .align_items(Alignment::Center);
+    let calendar_caption = if student.is_some() {
+        "Session days coloured by the student's status. Click a day to see the session."
+    } else {
+        "Session days coloured by attendance, red (nobody) to green (everybody). Click a day to see the session."
+    };
+    let session_detail: Element<Message> = match state.selected_session {
+        Some(session) => container(session_detail_view(report, session))
+            .style(theme::Container::Custom(Box::new(style::BorderedPanel)))
+            .padding(12)
+            .width(Length::Fill)
+            .into(),
+        None => Space::with_width(Length::Fill).into(),
+    };
+    let calendar = column![
+        calendar_title,
+        row![
+            scrollable(
+                Canvas::new(heatmap)
+                    .width(Length::Fixed(calendar_size.width))
+                    .height(Length::Fixed(calendar_size.height))
+            )
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Properties::new()
+            ))
+            .width(Length::Shrink)
+            .style(theme::Scrollable::Custom(Box::new(style::Scrollable))),
+            session_detail,
+        ]
+        .spacing(20)
+        .align_items(Alignment::Start),
+        text(calendar_caption).size(12).style(style::BASE01),
+    ]
+    .spacing(8);
+
+    let content = column![
+        text("CLASS DASHBOARD").size(14).style(style::BASE1),
+        headline,
+        section(
+            &trend,
+            ChartType::Trend,
+            Canvas::new(TrendChart::new(&trend))
+                .width(Length::Fill)
+                .height(Length::Fixed(220.0))
+                .into(),
+        ),
+        calendar,
+        row![
+            section(
+                &scores,
+                ChartType::Scores,
+                Canvas::new(BarChart::new(&scores))
+                    .width(Length::Fill)
+                    .height(Length::Fixed(200.0))
+                    .into(),
+            ),
+            section(
+                &lateness,
+                ChartType::Lateness,
+                Canvas::new(BarChart::new(&lateness))
+                    .width(Length::Fill)
+                    .height(Length::Fixed(200.0))
+                    .into(),
+            ),
+        ]
+        .spacing(20),
+    ]
+    .spacing(16)
+    .padding(16);
+
+    scrollable(content)
+        .height(Length::Fill)
+        .style(theme::Scrollable::Custom(Box::new(style::Scrollable)))
+        .into()
+}
+
+fn section(
+    chart: &Chart,
+    kind: ChartType,
+    canvas: Element<'static, Message>,
+) -> Element<'static, Message> {
+    column![
+        chart_title(&chart.title, kind),
+        canvas,
+        text(chart.caption.clone()).size(12).style(style::BASE01),
+    ]
+    .spacing(8)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Title of a chart section with a button exporting the chart as SVG or PNG.
+pub fn chart_title<'a>(title: &str, chart: ChartType) -> Element<'a, Message> {
+    row![
+        text(title.to_uppercase()).size(14).style(style::BASE1),
+        Space::with_width(Length::Fill),
+        button(text("EXPORT").size(12))
+            .on_press(Message::ExportChart(chart))
+            .style(theme::Button::Custom(Box::new(style::Button)))
+            .padding([4, 8]),
+    ]
+    .align_items(Alignment::Center)
+    .into()
+}
+
+fn label(content: String, position: Point, color: Color) -> canvas::Text {
+    canvas::Text {
+        content,
+        position,
+        color,
+        size: Pixels(11.0),
+        horizontal_alignment: Horizontal::Center,
+        vertical_alignment: Vertical::Bottom,
+        ..canvas::Text::default()
+    }
+}
+
+fn empty_chart(frame: &mut canvas::Frame, bounds: Rectangle) {
+    frame.fill_text(canvas::Text {
+        content: "No attendance data".to_string(),
+        position: Point::new(bounds.width / 2.0, bounds.height / 2.0),
+        color: style::BASE01,
+        size: Pixels(20.0),
+        horizontal_alignment: Horizontal::Center,
+        vertical_alignment: Vertical::Center,
+        ..canvas::Text::default()
+    });
+}
+
+/// Attendance rate of every session in order, as a line. Hovering shows the
+/// session date and rate.
+struct TrendChart {
+    marks: Vec<Mark>,
+    guides: Vec<Guide>,
+}
+
+impl TrendChart {
+    fn new(chart: &Chart) -> Self {
+        match &chart.plot {
+            Plot::Line { marks, guides } => Self {
+                marks: marks.clone(),
+                guides: guides.clone(),
+            },
+            _ => Self {
+                marks: Vec::new(),
+                guides: Vec::new(),
+            },
+        }
+    }
+}
+
+impl canvas::Program<Message> for TrendChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        if self.marks.is_empty() {
+            empty_chart(&mut frame, bounds);
+            return vec![frame.into_geometry()];
+        }
+
+        let top = LABEL_HEIGHT;
+        let plot_height = bounds.height - AXIS_HEIGHT - top;
+        let plot_width = bounds.width - PERCENT_AXIS_WIDTH;
+        let y_of = |rate: f32| top + plot_height * (1.0 - rate);
+        let step = plot_width / self.marks.len() as f32;
+        let x_of = |index: usize| PERCENT_AXIS_WIDTH + step * (index as f32 + 0.5);
+
+        for rate in [0.0, 0.5, 1.0] {
+            let y = y_of(rate);
+            frame.stroke(
+                &canvas::Path::line(
+                    Point::new(PERCENT_AXIS_WIDTH, y),
+                    Point::new(bounds.width, y),
+                ),
+                canvas::Stroke::default()
+                    .with_color(style::BASE02)
+                    .with_width(1.0),
+            );
+            frame.fill_text(canvas::Text {
+                content: format!("{:.0}%", rate * 100.0),
+                position: Point::new(PERCENT_AXIS_WIDTH - 6.0, y),
+                color: style::BASE00,
+                size: Pixels(11.0),
+                horizontal_alignment: Horizontal::Right,
+                vertical_alignment: Vertical::Center,
+                ..canvas::Text::default()
+            });
+        }
+        for guide in &self.guides {
+            let y = y_of(guide.value);
+            frame.stroke(
+                &canvas::Path::line(
+                    Point::new(PERCENT_AXIS_WIDTH, y),
+                    Point::new(bounds.width, y),
+                ),
+                canvas::Stroke {
+                    line_dash: canvas::LineDash {
+                        segments: &[4.0, 4.0],
+                        offset: 0,
+                    },
+                    ..canvas::Stroke::default()
+                        .with_color(style::tone_color(guide.tone))
+                        .with_width(1.0)
+                },
+            );
+        }
+
+        let line = canvas::Path::new(|builder| {
+            for (index, mark) in self.marks.iter().enumerate() {
+                let point = Point::new(x_of(index), y_of(mark.value));
+                if index == 0 {
+                    builder.move_to(point);
+                } else {
+                    builder.line_to(point);
+                }
+            }
+        });
+        frame.stroke(
+            &line,
+            canvas::Stroke::default()
+                .with_color(style::CYAN)
+                .with_width(2.0),
+        );
+        for (index, mark) in self.marks.iter().enumerate() {
+            frame.fill(
+                &canvas::Path::circle(Point::new(x_of(index), y_of(mark.value)), 3.5),
+                style::tone_color(mark.tone),
+            );
+        }
+
+        let last = self.marks.len() - 1;
+        for (index, alignment) in [(0, Horizontal::Left), (last, Horizontal::Right)] {
+            let x = if alignment == Horizontal::Left {
+                PERCENT_AXIS_WIDTH
+            } else {
+                bounds.width
+            };
+            frame.fill_text(canvas::Text {
+                horizontal_alignment: alignment,
+                ..label(
+                    self.marks[index].label.clone(),
+                    Point::new(x, bounds.height),
+                    style::BASE00,
+                )
+            });
+        }
+
+        if let Some(position) = cursor.position_in(bounds)
+            && position.x >= PERCENT_AXIS_WIDTH
+        {
+            let index = (((position.x - PERCENT_AXIS_WIDTH) / step) as usize).min(last);
+            let mark = &self.marks[index];
+            let x = x_of(index);
+            frame.stroke(
+                &canvas::Path::line(Point::new(x, top), Point::new(x, top + plot_height)),
+                canvas::Stroke::default()
+                    .with_color(style::BASE01)
+                    .with_width(1.0),
+            );
+            let alignment = if x > bounds.width / 2.0 {
+                Horizontal::Right
+            } else {
+                Horizontal::Left
+            };
+            frame.fill_text(canvas::Text {
+                horizontal_alignment: alignment,
+                ..label(
+                    format!("{}: {:.1}%", mark.label, mark.value * 100.0),
+                    Point::new(x, top),
+                    style::BASE1,
+                )
+            });
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Vertical bars with a label under each and the count on top.
+struct BarChart {
+    marks: Vec<Mark>,
+    max: f32,
+}
+
+impl BarChart {
+    fn new(chart: &Chart) -> Self {
+        match &chart.plot {
+            Plot::Bars { marks, max, .. } => Self {
+                marks: marks.clone(),
+                max: *max,
+            },
+            _ => Self {
+                marks: Vec::new(),
+                max: 0.0,
+            },
+        }
+    }
+}
+
+impl canvas::Program<Message> for BarChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        if self.max <= 0.0 {
+            empty_chart(&mut frame, bounds);
+            return vec![frame.into_geometry()];
+        }
+
+        let plot_height = bounds.height - AXIS_HEIGHT - LABEL_HEIGHT;
+        let slot = bounds.width / self.marks.len() as f32;
+        let bar_width = slot * 0.8;
+        for (index, mark) in self.marks.iter().enumerate() {
+            let center = slot * (index as f32 + 0.5);
+            let height = plot_height * (mark.value / self.max);
+            let top = LABEL_HEIGHT + plot_height - height;
+            frame.fill_rectangle(
+                Point::new(center - bar_width / 2.0, top),
+                Size::new(bar_width, height),
+                style::tone_color(mark.tone),
+            );
+            if mark.value > 0.0 {
+                frame.fill_text(label(
+                    charts::number_label(mark.value),
+                    Point::new(center, top - 2.0),
+                    style::BASE0,
+                ));
+            }
+            frame.fill_text(label(
+                mark.label.clone(),
+                Point::new(center, bounds.height),
+                style::BASE00,
+            ));
+        }
+        frame.stroke(
+            &canvas::Path::line(
+                Point::new(0.0, LABEL_HEIGHT + plot_height),
+                Point::new(bounds.width, LABEL_HEIGHT + plot_height),
+            ),
+            canvas::Stroke::default()
+                .with_color(style::BASE01)
+                .with_width(1.0),
+        );
+        vec![frame.into_geometry()]
+    }
+}
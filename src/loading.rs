@@ -0,0 +1,44 @@
+use std::sync::{Arc, Mutex};
+
+use iced::Subscription;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::subscription;
+use presence_core::{PresenceError, SessionCache, load_attendance_files_with};
+
+use crate::messages::Message;
+use crate::state::{LoadJob, user_message};
+
+/// Runs an analysis on a background thread, emitting `AnalysisProgress` for
+/// every parsed file and finally `AnalysisDone` or `AnalysisCancelled`. The
+/// files themselves are parsed in parallel by the core crate. iced keeps the
+/// subscription alive while the job with this id is in the state.
+pub fn analysis(job: &LoadJob, cache: Arc<Mutex<SessionCache>>) -> Subscription<Message> {
+    let job = job.clone();
+    subscription::channel(job.id, 64, move |mut output| async move {
+        let (sender, mut receiver) = mpsc::unbounded();
+        std::thread::spawn(move || {
+            let mut cache = cache
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let result = load_attendance_files_with(
+                &job.files,
+                job.config,
+                &mut cache,
+                &job.cancel,
+                |progress| {
+                    let _ = sender.unbounded_send(Message::AnalysisProgress(progress.clone()));
+                },
+            );
+            let done = match result {
+                Err(PresenceError::Cancelled) => Message::AnalysisCancelled,
+                result => Message::AnalysisDone(result.map_err(|error| user_message(&error))),
+            };
+            let _ = sender.unbounded_send(done);
+        });
+        while let Some(message) = receiver.next().await {
+            let _ = output.send(message).await;
+        }
+        iced::futures::future::pending().await
+    })
+}
@@ -1,23 +1,41 @@
-mod core;
+mod calendar;
+mod cli;
+mod dashboard;
+mod loading;
 mod messages;
+mod sessions;
 mod state;
 mod style;
+mod table;
+mod timeline;
+mod watch;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use core::{AttendanceReport, ReportFormat, StudentRecord};
 use iced::alignment::{Horizontal, Vertical};
-use iced::mouse;
 use iced::widget::canvas::{self, Canvas};
 use iced::widget::{
-    Column, Space, button, column, container, pick_list, row, scrollable, text, text_input,
+    Column, Space, button, checkbox, column, container, pick_list, progress_bar, row, scrollable,
+    text, text_input,
 };
 use iced::{
-    Alignment, Application, Color, Command, Element, Font, Length, Pixels, Point, Radians,
-    Rectangle, Renderer, Settings, Size, Theme, executor, theme,
+    Alignment, Application, Color, Command, Element, Event, Font, Length, Pixels, Point, Radians,
+    Rectangle, Renderer, Settings, Size, Subscription, Theme, executor, theme,
 };
+use iced::{event, keyboard, mouse, window};
 use messages::Message;
-use state::AppState;
+use presence_core::charts::{self, Chart, ChartSize, ChartType, Mark, Plot};
+use presence_core::watch::Snapshot;
+use presence_core::{
+    AttendanceReport, AttendanceStatus, CancelToken, ExportOptions, ReportFormat,
+    SessionAttendance, StudentRecord, scan_sources, summarize,
+};
+use presence_core::{overrides, profile, project};
+use state::{
+    AppState, ColumnChoice, ColumnDrag, LoadJob, OverrideDraft, RecentProject, SourceEntry,
+    StudentFilter, StudentSort, Tab,
+};
 
 const NAME_COLUMN_WIDTH: f32 = 150.0;
 const SURNAME_COLUMN_WIDTH: f32 = 150.0;
@@ -25,7 +43,21 @@ const ID_COLUMN_WIDTH: f32 = 120.0;
 const COUNT_COLUMN_WIDTH: f32 = 80.0;
 const SCORE_COLUMN_WIDTH: f32 = 100.0;
 
-fn main() -> iced::Result {
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::is_cli_invocation(&args) {
+        return cli::run(&args);
+    }
+    match run_gui() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_gui() -> iced::Result {
     App::run(Settings {
         antialiasing: true,
         window: iced::window::Settings {
@@ -42,6 +74,20 @@ fn labeled_input<'a>(
     value: &'a str,
     on_change: fn(String) -> Message,
 ) -> Element<'a, Message> {
+    validated_input(label, value, None, on_change)
+}
+
+/// A labeled input outlined in red, with the reason below, while `error` is set.
+fn validated_input<'a>(
+    label: &'a str,
+    value: &'a str,
+    error: Option<&'a str>,
+    on_change: fn(String) -> Message,
+) -> Element<'a, Message> {
+    let group: Box<dyn container::StyleSheet<Style = Theme>> = match error {
+        Some(_) => Box::new(style::InvalidInputGroup),
+        None => Box::new(style::InputGroup),
+    };
     container(
         column![
             text(label).size(14).style(style::YELLOW),
@@ -52,10 +98,11 @@ fn labeled_input<'a>(
                 .size(14)
                 .width(Length::Fill)
         ]
+        .push_maybe(error.map(|error| text(error).size(12).style(style::RED)))
         .spacing(2)
         .padding(6),
     )
-    .style(theme::Container::Custom(Box::new(style::InputGroup)))
+    .style(theme::Container::Custom(group))
     .width(Length::Fill)
     .into()
 }
@@ -64,6 +111,118 @@ struct App {
     state: AppState,
 }
 
+impl App {
+    /// Saves the current settings to `path` and makes it the active profile.
+    fn save_profile(&mut self, path: PathBuf) {
+        let result = self
+            .state
+            .to_profile()
+            .and_then(|profile| profile::save_profile(&profile, &path));
+        match result {
+            Ok(()) => {
+                self.state.status = format!("Profile saved to {}.", path.display());
+                self.state.profile_path = Some(path);
+            }
+            Err(error) => self.state.status = state::user_message(&error),
+        }
+    }
+
+    /// Adds picked, typed or dropped folders and files to the sources and
+    /// returns how many were new.
+    fn add_sources(&mut self, paths: impl IntoIterator<Item = PathBuf>) -> usize {
+        let added = paths
+            .into_iter()
+            .filter(|path| self.state.add_source(path.clone()))
+            .count();
+        self.state.status = if added == 0 {
+            "Already one of the sources.".to_string()
+        } else {
+            format!(
+                "{} sources selected. Click ANALYZE to analyze them.",
+                self.state.sources.len()
+            )
+        };
+        added
+    }
+
+    /// Analyzes the given exports in the background, replacing any analysis
+    /// that is still running. See `loading::analysis`.
+    fn analyze(&mut self, files: Vec<PathBuf>) -> Command<Message> {
+        if let Some(job) = self.state.job.take() {
+            job.cancel.cancel();
+        }
+        self.state.status = "Analyzing attendance files...".to_string();
+        self.state.is_busy = true;
+        self.state.discovered = None;
+        self.state.progress = None;
+        self.state.snapshot = Snapshot::of_files(files.iter().cloned());
+        self.state.next_job += 1;
+        self.state.job = Some(LoadJob {
+            id: self.state.next_job,
+            files,
+            config: self.state.to_config(),
+            cancel: CancelToken::new(),
+        });
+        Command::none()
+    }
+
+    /// Clears the running analysis once it finished or was cancelled.
+    fn finish_analysis(&mut self) {
+        self.state.is_busy = false;
+        self.state.job = None;
+        self.state.progress = None;
+    }
+
+    /// Opens a project and re-analyzes its sources.
+    fn open_project(&mut self, path: PathBuf) -> Command<Message> {
+        match project::load_project(&path) {
+            Ok(project) => {
+                self.state.apply_project(project);
+                self.state.status = format!("Opened project {}.", path.display());
+                self.state.project_path = Some(path.clone());
+                self.remember_project(&path);
+                if self.state.enabled_sources().is_empty() {
+                    Command::none()
+                } else {
+                    self.update(Message::RunAnalysis)
+                }
+            }
+            Err(error) => {
+                self.state.status = state::user_message(&error);
+                Command::none()
+            }
+        }
+    }
+
+    fn save_project(&mut self, path: PathBuf) {
+        let result = self
+            .state
+            .to_project()
+            .and_then(|project| project::save_project(&project, &path));
+        match result {
+            Ok(()) => {
+                self.state.status = format!("Project saved to {}.", path.display());
+                self.state.project_path = Some(path.clone());
+                self.remember_project(&path);
+            }
+            Err(error) => self.state.status = state::user_message(&error),
+        }
+    }
+
+    /// Adds a project to the recent list; failing to record it is not worth an error.
+    fn remember_project(&mut self, path: &std::path::Path) {
+        let _ = project::remember_project(path);
+        self.state.recent_projects = load_recent_projects();
+    }
+}
+
+fn load_recent_projects() -> Vec<RecentProject> {
+    project::recent_projects()
+        .into_iter()
+        .map(RecentProject)
+        .collect()
+}
+
 impl Application for App {
     type Executor = executor::Default;
     type Message = Message;
@@ -71,111 +230,511 @@ impl Application for App {
     type Flags = ();
 
     fn new(_flags: ()) -> (Self, Command<Message>) {
-        (
-            Self {
-                state: AppState::new(),
-            },
-            Command::none(),
-        )
+        let mut state = AppState::new();
+        if let Some(path) = profile::default_profile_path() {
+            if path.is_file() {
+                match profile::load_profile(&path) {
+                    Ok(profile) => state.apply_profile(&profile),
+                    Err(error) => state.status = state::user_message(&error),
+                }
+            }
+            state.profile_path = Some(path);
+        }
+        state.recent_projects = load_recent_projects();
+        (Self { state }, Command::none())
     }
 
     fn title(&self) -> String {
-        "Presence - Attendance Analyzer".to_string()
+        match self.state.project_name() {
+            Some(name) => format!("{name} - Presence"),
+            None => "Presence - Attendance Analyzer".to_string(),
+        }
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
-            Message::DirectoryChanged(value) => {
-                self.state.directory = value;
-                if self.state.directory.trim().is_empty() {
-                    self.state.status = "Select a directory to begin.".to_string();
+            Message::SourceInputChanged(value) => {
+                self.state.source_input = value;
+                Command::none()
+            }
+            Message::SubmitSourceInput => {
+                let path = PathBuf::from(self.state.source_input.trim());
+                if path.as_os_str().is_empty() {
+                    return Command::none();
+                }
+                if path.exists() {
+                    self.state.source_input.clear();
+                    self.add_sources([path]);
                 } else {
-                    self.state.status =
-                        "Directory selected. Click Generate Report to analyze.".to_string();
+                    self.state.status = format!("No such folder or file: {}", path.display());
                 }
                 Command::none()
             }
-            Message::PickDirectory => Command::perform(pick_directory(), Message::DirectoryPicked),
-            Message::DirectoryPicked(path) => {
-                if let Some(path) = path {
-                    self.state.directory = path.display().to_string();
-                    self.state.status =
-                        "Directory selected. Click Generate Report to analyze.".to_string();
+            Message::PickDirectory => Command::perform(pick_directories(), Message::SourcesPicked),
+            Message::PickFile => Command::perform(pick_files(), Message::SourcesPicked),
+            Message::SourcesPicked(paths) => {
+                if let Some(paths) = paths {
+                    self.add_sources(paths);
                 }
                 Command::none()
             }
-            Message::PickFile => Command::perform(pick_file(), Message::FilePicked),
-            Message::FilePicked(path) => {
-                if let Some(path) = path {
-                    self.state.directory = path.display().to_string();
-                    self.state.status =
-                        "File selected. Click Generate Report to analyze.".to_string();
+            Message::RemoveSource(index) => {
+                self.state.remove_source(index);
+                self.state.status = match self.state.sources.len() {
+                    0 => "Add or drop a folder or file to begin.".to_string(),
+                    count => format!("{count} sources selected."),
+                };
+                Command::none()
+            }
+            Message::SourceToggled(index, enabled) => {
+                self.state.set_source_enabled(index, enabled);
+                self.state.status = format!(
+                    "{} of {} sources enabled.",
+                    self.state.enabled_sources().len(),
+                    self.state.sources.len()
+                );
+                Command::none()
+            }
+            Message::DropHovered(hovering) => {
+                self.state.drop_hovering = hovering;
+                Command::none()
+            }
+            Message::SourceDropped(path) => {
+                self.state.drop_hovering = false;
+                let added = self.add_sources([path]);
+                let batch = self.state.collect_drop(added);
+                // Resolves after the rest of the drop's files were handled.
+                Command::perform(async move { batch }, Message::DropFinished)
+            }
+            Message::DropFinished(batch) => {
+                if self.state.finish_drop(batch) {
+                    self.update(Message::RunAnalysis)
+                } else {
+                    Command::none()
                 }
+            }
+            Message::AnalyzeOnDropToggled(value) => {
+                self.state.analyze_on_drop = value;
                 Command::none()
             }
             Message::ClassStartChanged(value) => {
                 self.state.class_start = value;
+                self.state.validate_config();
                 Command::none()
             }
             Message::ClassEndChanged(value) => {
                 self.state.class_end = value;
+                self.state.validate_config();
                 Command::none()
             }
             Message::LateMinutesChanged(value) => {
                 self.state.late_minutes = value;
+                self.state.validate_config();
                 Command::none()
             }
             Message::AbsentMinutesChanged(value) => {
                 self.state.absent_minutes = value;
+                self.state.validate_config();
                 Command::none()
             }
             Message::TotalPointsChanged(value) => {
                 self.state.total_points = value;
+                self.state.validate_config();
                 Command::none()
             }
             Message::LatePenaltyChanged(value) => {
                 self.state.late_penalty = value;
+                self.state.validate_config();
+                Command::none()
+            }
+            Message::MinAttendanceChanged(value) => {
+                self.state.min_attendance = value;
+                self.state.validate_config();
+                Command::none()
+            }
+            Message::ColumnsEditorToggled => {
+                self.state.columns_open = !self.state.columns_open;
+                Command::none()
+            }
+            Message::ExportColumnToggled(index, enabled) => {
+                if let Some(choice) = self.state.export_columns.get_mut(index) {
+                    choice.enabled = enabled;
+                }
+                Command::none()
+            }
+            Message::ExportColumnLabelChanged(index, label) => {
+                if let Some(choice) = self.state.export_columns.get_mut(index) {
+                    choice.label = label;
+                }
+                Command::none()
+            }
+            Message::MoveExportColumn(index, step) => {
+                self.state.move_column(index, step);
+                Command::none()
+            }
+            Message::GradeItemChanged(value) => {
+                self.state.grade_item = value;
+                Command::none()
+            }
+            Message::CourseNameChanged(value) => {
+                self.state.course_name = value;
+                Command::none()
+            }
+            Message::TermChanged(value) => {
+                self.state.term = value;
+                Command::none()
+            }
+            Message::StudentPagesToggled(value) => {
+                self.state.student_pages = value;
                 Command::none()
             }
             Message::ReportFormatChanged(format) => {
                 self.state.report_format = format;
                 Command::none()
             }
+            Message::RecursiveToggled(value) => {
+                self.state.recursive = value;
+                self.state.discovered = None;
+                Command::none()
+            }
+            Message::IncludeChanged(value) => {
+                self.state.include = value;
+                self.state.discovered = None;
+                Command::none()
+            }
+            Message::ExcludeChanged(value) => {
+                self.state.exclude = value;
+                self.state.discovered = None;
+                Command::none()
+            }
+            Message::ScanSource => {
+                let sources = self.state.enabled_sources();
+                match scan_sources(&sources, &self.state.to_scan_options()) {
+                    // Files picked one by one need no confirmation
+                    Ok(files) if sources.iter().all(|source| source.is_file()) => {
+                        self.analyze(files)
+                    }
+                    Ok(files) => {
+                        self.state.status = format!(
+                            "Found {} attendance files. Review the list and confirm to analyze.",
+                            files.len()
+                        );
+                        self.state.discovered = Some(files);
+                        Command::none()
+                    }
+                    Err(error) => {
+                        self.state.status = state::user_message(&error);
+                        self.state.discovered = None;
+                        Command::none()
+                    }
+                }
+            }
+            Message::ConfirmAnalysis => match self.state.discovered.take() {
+                Some(files) => self.analyze(files),
+                None => Command::none(),
+            },
+            Message::CancelScan => {
+                self.state.discovered = None;
+                self.state.status = "Analysis cancelled.".to_string();
+                Command::none()
+            }
             Message::RunAnalysis => {
-                self.state.status = "Analyzing attendance files...".to_string();
-                self.state.is_busy = true;
-                let config = self.state.to_config();
-                let directory = PathBuf::from(self.state.directory.clone());
-                Command::perform(load_attendance(directory, config), Message::AnalysisDone)
+                match scan_sources(&self.state.enabled_sources(), &self.state.to_scan_options()) {
+                    Ok(files) => self.analyze(files),
+                    Err(error) => {
+                        self.update(Message::AnalysisDone(Err(state::user_message(&error))))
+                    }
+                }
+            }
+            Message::AnalysisProgress(progress) => {
+                let file = progress
+                    .file
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                self.state.status = format!(
+                    "Parsed {file} ({}/{} files)...",
+                    progress.done, progress.total
+                );
+                self.state.progress = Some(progress);
+                Command::none()
+            }
+            Message::CancelAnalysis => {
+                if let Some(job) = &self.state.job {
+                    job.cancel.cancel();
+                    self.state.status = "Cancelling analysis...".to_string();
+                }
+                Command::none()
+            }
+            Message::AnalysisCancelled => {
+                self.finish_analysis();
+                self.state.change_notice = None;
+                self.state.reanalyze_pending = false;
+                self.state.status = match self.state.report {
+                    Some(_) => "Analysis cancelled. Showing the previous report.".to_string(),
+                    None => "Analysis cancelled.".to_string(),
+                };
+                Command::none()
             }
             Message::AnalysisDone(result) => {
-                self.state.is_busy = false;
+                self.finish_analysis();
                 match result {
                     Ok(report) => {
+                        let parsed = self
+                            .state
+                            .session_cache
+                            .lock()
+                            .map(|cache| cache.parsed())
+                            .unwrap_or_default();
                         self.state.status = format!(
-                            "Loaded {} students across {} sessions.",
+                            "Loaded {} students across {} sessions ({parsed} files parsed).",
                             report.students.len(),
                             report.sessions
                         );
-                        self.state.report = Some(report);
+                        if let Some(notice) = self.state.change_notice.take() {
+                            self.state
+                                .status
+                                .push_str(&format!(" Source changed: {notice}."));
+                        }
+                        let mut report = report;
+                        overrides::apply_overrides(&mut report, &self.state.overrides);
+                        self.state.set_report(Some(report));
                     }
                     Err(error) => {
                         self.state.status = error;
-                        self.state.report = None;
+                        self.state.set_report(None);
+                        self.state.change_notice = None;
                     }
                 }
+                if std::mem::take(&mut self.state.reanalyze_pending) {
+                    return self.update(Message::SourceChanged);
+                }
                 Command::none()
             }
             Message::SelectStudent(index) => {
                 self.state.selected_student = Some(index);
+                self.state.override_draft = None;
+                Command::none()
+            }
+            Message::ClearStudentSelection => {
+                self.state.selected_student = None;
+                self.state.override_draft = None;
+                Command::none()
+            }
+            Message::SelectSession(session) => {
+                self.state.selected_session = Some(session);
+                Command::none()
+            }
+            Message::CloseSession => {
+                self.state.selected_session = None;
+                Command::none()
+            }
+            Message::StudentSearchChanged(query) => {
+                self.state.student_query = query;
+                Command::none()
+            }
+            Message::StudentSortChanged(sort) => {
+                self.state.student_sort = sort;
+                Command::none()
+            }
+            Message::SortDirectionToggled => {
+                self.state.sort_descending = !self.state.sort_descending;
+                Command::none()
+            }
+            Message::StudentFilterToggled(filter) => {
+                self.state.student_filter = match self.state.student_filter {
+                    Some(active) if active == filter => None,
+                    _ => Some(filter),
+                };
+                Command::none()
+            }
+            Message::TabSelected(tab) => {
+                self.state.tab = tab;
+                Command::none()
+            }
+            Message::TableViewToggled => {
+                self.state.table_view = !self.state.table_view;
+                Command::none()
+            }
+            Message::ColumnResizeStarted(column) => {
+                self.state.column_drag =
+                    self.state
+                        .column_widths
+                        .get(column)
+                        .map(|&width| ColumnDrag {
+                            column,
+                            origin: None,
+                            width,
+                        });
+                Command::none()
+            }
+            Message::ColumnResizeMoved(x) => {
+                self.state.drag_column(x);
+                Command::none()
+            }
+            Message::ColumnResizeEnded => {
+                self.state.column_drag = None;
+                Command::none()
+            }
+            Message::MoveSelection(step) => {
+                let Some(position) = self.state.move_selection(step) else {
+                    return Command::none();
+                };
+                let rows = self.state.visible_students().len().saturating_sub(1).max(1);
+                let id = if self.state.table_view {
+                    table::rows_id()
+                } else {
+                    student_list_id()
+                };
+                scrollable::snap_to(
+                    id,
+                    scrollable::RelativeOffset {
+                        x: 0.0,
+                        y: position as f32 / rows as f32,
+                    },
+                )
+            }
+            Message::EditOverride(session) => {
+                if let Some(student) = self.state.selected_student {
+                    self.state.edit_override(student, session);
+                }
+                Command::none()
+            }
+            Message::OverrideStatusChanged(status) => {
+                if let Some(draft) = &mut self.state.override_draft {
+                    draft.status = status;
+                }
+                Command::none()
+            }
+            Message::OverrideReasonChanged(reason) => {
+                if let Some(draft) = &mut self.state.override_draft {
+                    draft.reason = reason;
+                }
+                Command::none()
+            }
+            Message::SaveOverride => {
+                self.state.status = match self.state.save_override() {
+                    Ok(()) => "Override saved. Scores were recalculated.".to_string(),
+                    Err(error) => state::user_message(&error),
+                };
+                Command::none()
+            }
+            Message::RemoveOverride => {
+                self.state.remove_override();
+                self.state.status = "Override removed.".to_string();
+                Command::none()
+            }
+            Message::CancelOverride => {
+                self.state.override_draft = None;
+                Command::none()
+            }
+            Message::ExportAuditLog => {
+                if self.state.report.is_none() {
+                    self.state.status = "Analyze the attendance files first.".to_string();
+                    Command::none()
+                } else if self.state.overrides.is_empty() {
+                    self.state.status = "No overrides have been recorded.".to_string();
+                    Command::none()
+                } else {
+                    let stem = self
+                        .state
+                        .to_export_options()
+                        .map(|options| options.file_stem())
+                        .unwrap_or_else(|_| "attendance".to_string());
+                    Command::perform(
+                        pick_audit_log_destination(format!("{stem}-overrides.csv")),
+                        Message::AuditLogDestinationPicked,
+                    )
+                }
+            }
+            Message::AuditLogDestinationPicked(path) => {
+                if let (Some(path), Some(report)) = (path, &self.state.report) {
+                    self.state.status =
+                        match overrides::audit_log_to_path(report, &self.state.overrides, &path) {
+                            Ok(()) => format!("Audit log saved to {}.", path.display()),
+                            Err(error) => state::user_message(&error),
+                        };
+                }
+                Command::none()
+            }
+            Message::ChartSizeChanged(value) => {
+                self.state.chart_size = value;
+                Command::none()
+            }
+            Message::ExportChart(chart) => {
+                let Some(report) = &self.state.report else {
+                    self.state.status = "Analyze the attendance files first.".to_string();
+                    return Command::none();
+                };
+                let student = if chart.is_per_student() {
+                    match self
+                        .state
+                        .selected_student
+                        .and_then(|index| report.students.get(index))
+                    {
+                        Some(student) => Some(student),
+                        None => {
+                            self.state.status = "Select a student first.".to_string();
+                            return Command::none();
+                        }
+                    }
+                } else {
+                    None
+                };
+                if let Err(error) = self.state.chart_size.parse::<ChartSize>() {
+                    self.state.status = state::user_message(&error);
+                    return Command::none();
+                }
+                let stem = self
+                    .state
+                    .to_export_options()
+                    .map(|options| options.file_stem())
+                    .unwrap_or_else(|_| "attendance".to_string());
+                Command::perform(
+                    pick_chart_destination(charts::chart_file_name(
+                        &stem,
+                        chart,
+                        student,
+                        charts::ChartFormat::Svg,
+                    )),
+                    move |path| Message::ChartDestinationPicked(chart, path),
+                )
+            }
+            Message::ChartDestinationPicked(chart, path) => {
+                if let (Some(path), Some(report)) = (path, &self.state.report) {
+                    let student = self
+                        .state
+                        .selected_student
+                        .and_then(|index| report.students.get(index));
+                    let Some(description) = chart.build(report, &summarize(report), student) else {
+                        self.state.status = "Select a student first.".to_string();
+                        return Command::none();
+                    };
+                    let result = self
+                        .state
+                        .chart_size
+                        .parse::<ChartSize>()
+                        .and_then(|size| charts::export_chart(&description, size, &path));
+                    self.state.status = match result {
+                        Ok(path) => format!("Chart saved to {}.", path.display()),
+                        Err(error) => state::user_message(&error),
+                    };
+                }
                 Command::none()
             }
             Message::ExportReport => {
                 if let Some(report) = self.state.report.clone() {
+                    let options = match self.state.to_export_options() {
+                        Ok(options) => options,
+                        Err(error) => {
+                            self.state.status = state::user_message(&error);
+                            return Command::none();
+                        }
+                    };
                     self.state.status = "Exporting report...".to_string();
                     self.state.is_busy = true;
                     let format = self.state.report_format;
-                    Command::perform(save_report(report, format), Message::ReportSaved)
+                    Command::perform(save_report(report, format, options), Message::ReportSaved)
                 } else {
                     self.state.status = "Load attendance data before exporting.".to_string();
                     Command::none()
@@ -191,22 +750,245 @@ impl Application for App {
                 }
                 Command::none()
             }
+            Message::ExportAll => {
+                if let Some(report) = self.state.report.clone() {
+                    let options = match self.state.to_export_options() {
+                        Ok(options) => options,
+                        Err(error) => {
+                            self.state.status = state::user_message(&error);
+                            return Command::none();
+                        }
+                    };
+                    self.state.status = "Exporting reports...".to_string();
+                    self.state.is_busy = true;
+                    Command::perform(save_all_reports(report, options), Message::ReportsSaved)
+                } else {
+                    self.state.status = "Load attendance data before exporting.".to_string();
+                    Command::none()
+                }
+            }
+            Message::ReportsSaved(result) => {
+                self.state.is_busy = false;
+                match result {
+                    Ok(paths) => {
+                        let folder = paths
+                            .first()
+                            .and_then(|path| path.parent())
+                            .map(|folder| folder.display().to_string())
+                            .unwrap_or_default();
+                        self.state.status = format!("{} reports saved to {folder}", paths.len());
+                    }
+                    Err(error) => self.state.status = error,
+                }
+                Command::none()
+            }
+            Message::SaveProfile => match self.state.profile_path.clone() {
+                Some(path) => {
+                    self.save_profile(path);
+                    Command::none()
+                }
+                None => Command::perform(
+                    pick_profile_destination(format!("{}.toml", self.state.profile_name())),
+                    Message::ProfileDestinationPicked,
+                ),
+            },
+            Message::LoadProfile => Command::perform(pick_profile(), Message::ProfilePicked),
+            Message::ProfilePicked(path) => {
+                if let Some(path) = path {
+                    match profile::load_profile(&path) {
+                        Ok(profile) => {
+                            self.state.apply_profile(&profile);
+                            self.state.status = match profile.to_export_options() {
+                                Ok(_) => format!("Loaded profile {}.", path.display()),
+                                Err(error) => format!(
+                                    "Loaded profile {}. {} Using the default columns.",
+                                    path.display(),
+                                    state::user_message(&error)
+                                ),
+                            };
+                            self.state.profile_path = Some(path);
+                        }
+                        Err(error) => self.state.status = state::user_message(&error),
+                    }
+                }
+                Command::none()
+            }
+            Message::DuplicateProfile => Command::perform(
+                pick_profile_destination(format!("{}-copy.toml", self.state.profile_name())),
+                Message::ProfileDestinationPicked,
+            ),
+            Message::ProfileDestinationPicked(path) => {
+                if let Some(path) = path {
+                    self.save_profile(path);
+                }
+                Command::none()
+            }
+            Message::OpenProject => Command::perform(pick_project(), Message::ProjectPicked),
+            Message::ProjectPicked(path) => match path {
+                Some(path) => self.open_project(path),
+                None => Command::none(),
+            },
+            Message::RecentProjectSelected(RecentProject(path)) => self.open_project(path),
+            Message::SaveProject => match self.state.project_path.clone() {
+                Some(path) => {
+                    self.save_project(path);
+                    Command::none()
+                }
+                None => {
+                    let name = match self.state.course_name.trim() {
+                        "" => "course".to_string(),
+                        course => course.to_string(),
+                    };
+                    Command::perform(
+                        pick_project_destination(format!("{name}.{}", project::PROJECT_EXTENSION)),
+                        Message::ProjectDestinationPicked,
+                    )
+                }
+            },
+            Message::ProjectDestinationPicked(path) => {
+                if let Some(path) = path {
+                    self.save_project(path);
+                }
+                Command::none()
+            }
+            Message::NotesChanged(value) => {
+                self.state.notes = value;
+                Command::none()
+            }
+            Message::WatchToggled(value) => {
+                self.state.watching = value;
+                if !value {
+                    self.state.status = "Stopped watching the source.".to_string();
+                    Command::none()
+                } else if self.state.enabled_sources().is_empty() {
+                    self.state.status = "Add a source to watch.".to_string();
+                    Command::none()
+                } else if self.state.report.is_none() && !self.state.is_busy {
+                    self.update(Message::RunAnalysis)
+                } else {
+                    self.state.status = "Watching the source for new exports.".to_string();
+                    Command::none()
+                }
+            }
+            Message::SourceChanged => {
+                if self.state.is_busy {
+                    self.state.reanalyze_pending = true;
+                    return Command::none();
+                }
+                let changes = Snapshot::capture_all(
+                    &self.state.enabled_sources(),
+                    &self.state.to_scan_options(),
+                )
+                .changes_since(&self.state.snapshot);
+                if changes.is_empty() {
+                    return Command::none();
+                }
+                self.state.change_notice = Some(changes.to_string());
+                self.update(Message::RunAnalysis)
+            }
+            Message::WatchFailed(error) => {
+                self.state.watching = false;
+                self.state.status = error;
+                Command::none()
+            }
         }
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        let watchers = if self.state.watching {
+            Subscription::batch(
+                self.state
+                    .enabled_sources()
+                    .into_iter()
+                    .map(|source| watch::source_changes(source, self.state.recursive)),
+            )
+        } else {
+            Subscription::none()
+        };
+        let analysis = match &self.state.job {
+            Some(job) => loading::analysis(job, self.state.session_cache.clone()),
+            None => Subscription::none(),
+        };
+        let keyboard = match self.state.report {
+            Some(_) => keyboard::on_key_press(navigation_key),
+            None => Subscription::none(),
+        };
+        let resize = match self.state.column_drag {
+            Some(_) => event::listen_with(column_resize_event),
+            None => Subscription::none(),
+        };
+        let drop = event::listen_with(file_drop_event);
+        Subscription::batch([watchers, analysis, keyboard, resize, drop])
+    }
+
     fn view(&self) -> Element<'_, Message> {
+        let small_button = |label, message| {
+            button(text(label).size(12))
+                .on_press(message)
+                .style(theme::Button::Custom(Box::new(style::Button)))
+                .padding([4, 8])
+        };
+        let project_label = match self.state.project_name() {
+            Some(name) => format!("PROJECT: {name}"),
+            None => "PROJECT: untitled".to_string(),
+        };
+        let source_label = if self.state.drop_hovering {
+            text("Drop to add as sources").size(12).style(style::CYAN)
+        } else {
+            text(format!(
+                "Attendance Sources ({} of {} enabled)",
+                self.state.enabled_sources().len(),
+                self.state.sources.len()
+            ))
+            .size(12)
+            .style(style::BASE00)
+        };
         let input_section = column![
-            text("CONFIGURATION").size(14).style(style::BASE1),
+            row![
+                text(project_label).size(14).style(style::BASE1),
+                Space::with_width(Length::Fill),
+                pick_list(
+                    self.state.recent_projects.clone(),
+                    None::<RecentProject>,
+                    Message::RecentProjectSelected
+                )
+                .placeholder("Recent projects")
+                .text_size(12)
+                .style(theme::PickList::Custom(
+                    std::rc::Rc::new(style::PickList),
+                    std::rc::Rc::new(style::Menu)
+                )),
+                small_button("OPEN", Message::OpenProject),
+                small_button("SAVE PROJECT", Message::SaveProject),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+            row![
+                text("CONFIGURATION").size(14).style(style::BASE1),
+                Space::with_width(Length::Fill),
+                text(format!("Profile: {}", self.state.profile_name()))
+                    .size(12)
+                    .style(style::BASE00),
+                small_button("SAVE", Message::SaveProfile),
+                small_button("LOAD", Message::LoadProfile),
+                small_button("DUPLICATE", Message::DuplicateProfile),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
             Space::with_height(Length::Fixed(10.0)),
             row![
                 column![
-                    text("Attendance Source").size(12).style(style::BASE00),
+                    source_label,
                     row![
-                        text_input("Directory or file path...", &self.state.directory)
-                            .on_input(Message::DirectoryChanged)
-                            .style(theme::TextInput::Custom(Box::new(style::TextInput)))
-                            .padding(8)
-                            .width(Length::Fill),
+                        text_input(
+                            "Add a folder or file path, or drop them onto the window...",
+                            &self.state.source_input
+                        )
+                        .on_input(Message::SourceInputChanged)
+                        .on_submit(Message::SubmitSourceInput)
+                        .style(theme::TextInput::Custom(Box::new(style::TextInput)))
+                        .padding(8)
+                        .width(Length::Fill),
                         button(text("Folder").size(14))
                             .on_press(Message::PickDirectory)
                             .style(theme::Button::Custom(Box::new(style::Button)))
@@ -214,17 +996,61 @@ impl Application for App {
                         button(text("File").size(14))
                             .on_press(Message::PickFile)
                             .style(theme::Button::Custom(Box::new(style::Button)))
-                            .padding(8)
+                            .padding(8),
+                        checkbox("Watch", self.state.watching)
+                            .on_toggle(Message::WatchToggled)
+                            .style(theme::Checkbox::Custom(Box::new(style::Checkbox)))
+                            .text_size(14)
+                            .size(16)
+                            .width(Length::Shrink),
+                        checkbox("Analyze on drop", self.state.analyze_on_drop)
+                            .on_toggle(Message::AnalyzeOnDropToggled)
+                            .style(theme::Checkbox::Custom(Box::new(style::Checkbox)))
+                            .text_size(14)
+                            .size(16)
+                            .width(Length::Shrink),
                     ]
                     .spacing(8)
+                    .align_items(Alignment::Center),
+                    source_list_view(&self.state.sources),
+                    row![
+                        checkbox("Subfolders", self.state.recursive)
+                            .on_toggle(Message::RecursiveToggled)
+                            .style(theme::Checkbox::Custom(Box::new(style::Checkbox)))
+                            .text_size(14)
+                            .size(16)
+                            .width(Length::Shrink),
+                        labeled_input(
+                            "Include (globs)",
+                            &self.state.include,
+                            Message::IncludeChanged
+                        ),
+                        labeled_input(
+                            "Exclude (globs)",
+                            &self.state.exclude,
+                            Message::ExcludeChanged
+                        ),
+                    ]
+                    .spacing(8)
+                    .align_items(Alignment::Center)
                 ]
                 .width(Length::FillPortion(2))
                 .spacing(4),
                 column![
                     text("Class Time").size(12).style(style::BASE00),
                     row![
-                        labeled_input("Start", &self.state.class_start, Message::ClassStartChanged),
-                        labeled_input("End", &self.state.class_end, Message::ClassEndChanged),
+                        validated_input(
+                            "Start",
+                            &self.state.class_start,
+                            self.state.config_error("class_start"),
+                            Message::ClassStartChanged
+                        ),
+                        validated_input(
+                            "End",
+                            &self.state.class_end,
+                            self.state.config_error("class_end"),
+                            Message::ClassEndChanged
+                        ),
                     ]
                     .spacing(8)
                     .align_items(Alignment::Center)
@@ -237,14 +1063,16 @@ impl Application for App {
                 column![
                     text("Thresholds (Min)").size(12).style(style::BASE00),
                     row![
-                        labeled_input(
+                        validated_input(
                             "Late",
                             &self.state.late_minutes,
+                            self.state.config_error("late_minutes"),
                             Message::LateMinutesChanged
                         ),
-                        labeled_input(
+                        validated_input(
                             "Absent",
                             &self.state.absent_minutes,
+                            self.state.config_error("absent_minutes"),
                             Message::AbsentMinutesChanged
                         ),
                     ]
@@ -256,16 +1084,24 @@ impl Application for App {
                 column![
                     text("Grading (Points)").size(12).style(style::BASE00),
                     row![
-                        labeled_input(
+                        validated_input(
                             "Total",
                             &self.state.total_points,
+                            self.state.config_error("total_points"),
                             Message::TotalPointsChanged
                         ),
-                        labeled_input(
+                        validated_input(
                             "Late Pts.",
                             &self.state.late_penalty,
+                            self.state.config_error("late_penalty"),
                             Message::LatePenaltyChanged
                         ),
+                        validated_input(
+                            "Min. %",
+                            &self.state.min_attendance,
+                            self.state.config_error("min_attendance"),
+                            Message::MinAttendanceChanged
+                        ),
                     ]
                     .spacing(8)
                     .align_items(Alignment::Center)
@@ -285,13 +1121,22 @@ impl Application for App {
                             std::rc::Rc::new(style::Menu)
                         )),
                         button(text("ANALYZE").size(14))
-                            .on_press(Message::RunAnalysis)
+                            .on_press_maybe(
+                                self.state
+                                    .config_errors
+                                    .is_empty()
+                                    .then_some(Message::ScanSource)
+                            )
                             .style(theme::Button::Custom(Box::new(style::PrimaryButton)))
                             .padding(8),
                         button(text("EXPORT").size(14))
                             .on_press(Message::ExportReport)
                             .style(theme::Button::Custom(Box::new(style::Button)))
                             .padding(8),
+                        button(text("EXPORT ALL").size(14))
+                            .on_press(Message::ExportAll)
+                            .style(theme::Button::Custom(Box::new(style::Button)))
+                            .padding(8),
                     ]
                     .spacing(8)
                     .align_items(Alignment::Center)
@@ -299,6 +1144,55 @@ impl Application for App {
                 .spacing(4)
             ]
             .spacing(20),
+            row![
+                column![
+                    text("Report Details").size(12).style(style::BASE00),
+                    row![
+                        labeled_input(
+                            "Course",
+                            &self.state.course_name,
+                            Message::CourseNameChanged
+                        ),
+                        labeled_input("Term", &self.state.term, Message::TermChanged),
+                        button(
+                            text(format!("COLUMNS ({})", self.state.selected_columns().len()))
+                                .size(14)
+                        )
+                        .on_press(Message::ColumnsEditorToggled)
+                        .style(if self.state.columns_open {
+                            theme::Button::Custom(Box::new(style::PrimaryButton))
+                        } else {
+                            theme::Button::Custom(Box::new(style::Button))
+                        })
+                        .padding(8),
+                        labeled_input(
+                            "Grade Item (LMS)",
+                            &self.state.grade_item,
+                            Message::GradeItemChanged
+                        ),
+                        labeled_input(
+                            "Chart Size (px)",
+                            &self.state.chart_size,
+                            Message::ChartSizeChanged
+                        ),
+                        labeled_input("Notes", &self.state.notes, Message::NotesChanged),
+                        checkbox("Student pages (PDF)", self.state.student_pages)
+                            .on_toggle(Message::StudentPagesToggled)
+                            .style(theme::Checkbox::Custom(Box::new(style::Checkbox)))
+                            .text_size(14)
+                            .size(16)
+                            .width(Length::Shrink),
+                    ]
+                    .spacing(8)
+                    .align_items(Alignment::Center)
+                ]
+                .push_maybe(
+                    self.state
+                        .columns_open
+                        .then(|| column_editor(&self.state.export_columns))
+                )
+                .spacing(4),
+            ],
         ]
         .spacing(12)
         .padding(16);
@@ -309,11 +1203,14 @@ impl Application for App {
 
         let (students_list, detail_view): (Element<Message>, Element<Message>) =
             if let Some(report) = &self.state.report {
-                let list = report.students.iter().enumerate().fold(
-                    Column::new().spacing(4),
-                    |col, (index, student)| {
+                let visible = self.state.visible_students();
+                let list = visible
+                    .iter()
+                    .map(|&index| (index, &report.students[index]))
+                    .fold(Column::new().spacing(4), |col, (index, student)| {
+                        let marker = if student.has_overrides() { "*" } else { "" };
                         let label = format!(
-                            "{:<20} {:<20} ({})",
+                            "{:<20} {:<20} ({}){marker}",
                             student.surname, student.name, student.id
                         );
                         let student_button = button(text(label).size(14))
@@ -324,13 +1221,28 @@ impl Application for App {
                             })
                             .width(Length::Fill);
                         col.push(student_button.on_press(Message::SelectStudent(index)))
-                    },
+                    });
+                let list = if visible.is_empty() {
+                    container(text("No students match.").style(style::BASE01))
+                        .center_x()
+                        .center_y()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .into()
+                } else if self.state.table_view {
+                    table::class_table(&self.state, report)
+                } else {
+                    scrollable(container(list).padding(8))
+                        .id(student_list_id())
+                        .height(Length::Fill)
+                        .style(theme::Scrollable::Custom(Box::new(style::Scrollable)))
+                        .into()
+                };
+                let detail = student_detail_view(
+                    report,
+                    self.state.selected_student,
+                    self.state.override_draft.as_ref(),
                 );
-                let list = scrollable(container(list).padding(8))
-                    .height(Length::Fill)
-                    .style(theme::Scrollable::Custom(Box::new(style::Scrollable)))
-                    .into();
-                let detail = student_detail_view(report, self.state.selected_student);
                 (list, detail)
             } else {
                 let placeholder = container(text("No report loaded yet.").style(style::BASE01))
@@ -353,14 +1265,40 @@ impl Application for App {
 
         let list_container = container(
             column![
-                text("STUDENTS").size(14).style(style::BASE1),
+                row![
+                    text("STUDENTS").size(14).style(style::BASE1),
+                    Space::with_width(Length::Fill),
+                    text(format!(
+                        "Overrides: {}",
+                        overrides::active_overrides(&self.state.overrides).len()
+                    ))
+                    .size(12)
+                    .style(style::BASE00),
+                    small_button("AUDIT LOG", Message::ExportAuditLog),
+                    small_button(
+                        if self.state.table_view {
+                            "LIST VIEW"
+                        } else {
+                            "TABLE VIEW"
+                        },
+                        Message::TableViewToggled
+                    ),
+                ]
+                .spacing(8)
+                .align_items(Alignment::Center),
                 Space::with_height(Length::Fixed(10.0)),
+                student_list_controls(&self.state),
                 students_list
             ]
+            .spacing(6)
             .padding(16),
         )
         .style(theme::Container::Custom(Box::new(style::Panel)))
-        .width(Length::FillPortion(1))
+        .width(if self.state.table_view {
+            Length::Fill
+        } else {
+            Length::FillPortion(1)
+        })
         .height(Length::Fill);
 
         let detail_container = container(detail_view)
@@ -368,25 +1306,77 @@ impl Application for App {
             .width(Length::FillPortion(2))
             .height(Length::Fill);
 
-        let status_bar = container(
-            row![
-                text("STATUS: ").style(style::BASE1).size(14),
-                text(&self.state.status).style(style::BASE0).size(14)
-            ]
-            .padding(4),
-        )
-        .style(theme::Container::Custom(Box::new(style::BorderedPanel)))
-        .width(Length::Fill);
-
-        let content = column![
-            input_container,
-            row![list_container, detail_container]
-                .height(Length::Fill)
-                .spacing(16),
-            status_bar
+        let mut status_row = row![
+            text("STATUS: ").style(style::BASE1).size(14),
+            text(&self.state.status).style(style::BASE0).size(14),
+            Space::with_width(Length::Fill),
         ]
-        .spacing(16)
-        .padding(16);
+        .spacing(8)
+        .align_items(Alignment::Center)
+        .padding(4);
+        if self.state.job.is_some() {
+            let (done, total) = self
+                .state
+                .progress
+                .as_ref()
+                .map_or((0, 1), |progress| (progress.done, progress.total));
+            status_row = status_row
+                .push(
+                    progress_bar(0.0..=total as f32, done as f32)
+                        .width(Length::Fixed(200.0))
+                        .height(Length::Fixed(12.0))
+                        .style(theme::ProgressBar::Custom(Box::new(style::ProgressBar))),
+                )
+                .push(small_button("CANCEL", Message::CancelAnalysis));
+        }
+        let status_bar = container(status_row)
+            .style(theme::Container::Custom(Box::new(style::BorderedPanel)))
+            .width(Length::Fill);
+
+        let tabs = Tab::ALL.iter().fold(
+            row![].spacing(6).align_items(Alignment::Center),
+            |tabs, &tab| {
+                tabs.push(
+                    button(text(tab.to_string()).size(14))
+                        .on_press(Message::TabSelected(tab))
+                        .style(if self.state.tab == tab {
+                            theme::Button::Custom(Box::new(style::PrimaryButton))
+                        } else {
+                            theme::Button::Custom(Box::new(style::Button))
+                        })
+                        .padding([6, 12]),
+                )
+            },
+        );
+
+        let body: Element<Message> =
+            match (&self.state.discovered, self.state.tab, &self.state.report) {
+                (Some(files), _, _) => discovered_view(
+                    &self.state.enabled_sources(),
+                    files,
+                    self.state.config_errors.is_empty(),
+                ),
+                (None, Tab::Dashboard, Some(report)) => {
+                    panel(dashboard::dashboard_view(&self.state, report))
+                }
+                (None, Tab::Sessions, Some(report)) => sessions::sessions_view(&self.state, report),
+                (None, Tab::Dashboard | Tab::Sessions, None) => panel(
+                    container(text("No report loaded yet.").style(style::BASE01))
+                        .center_x()
+                        .center_y()
+                        .width(Length::Fill)
+                        .height(Length::Fill),
+                ),
+                (None, Tab::Students, _) if self.state.table_view => list_container.into(),
+                (None, Tab::Students, _) => row![list_container, detail_container]
+                    .height(Length::Fill)
+                    .spacing(16)
+                    .into(),
+            };
+
+        let content = column![input_container, tabs, body, status_bar]
+            .spacing(16)
+            .padding(16);
 
         container(content)
             .height(Length::Fill)
@@ -396,10 +1386,122 @@ impl Application for App {
     }
 }
 
-fn student_detail_view(
-    report: &AttendanceReport,
+/// Full-size panel around a tab's content.
+fn panel<'a>(content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    container(content)
+        .style(theme::Container::Custom(Box::new(style::Panel)))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+fn student_list_id() -> scrollable::Id {
+    scrollable::Id::new("student-list")
+}
+
+/// Arrow and page keys move the selection through the student list or table.
+fn navigation_key(key: keyboard::Key, _modifiers: keyboard::Modifiers) -> Option<Message> {
+    let step = match key {
+        keyboard::Key::Named(keyboard::key::Named::ArrowUp) => -1,
+        keyboard::Key::Named(keyboard::key::Named::ArrowDown) => 1,
+        keyboard::Key::Named(keyboard::key::Named::PageUp) => -10,
+        keyboard::Key::Named(keyboard::key::Named::PageDown) => 10,
+        _ => return None,
+    };
+    Some(Message::MoveSelection(step))
+}
+
+/// Files and folders dragged onto the window from a file manager.
+fn file_drop_event(event: Event, _status: event::Status) -> Option<Message> {
+    match event {
+        Event::Window(_, window::Event::FileHovered(_)) => Some(Message::DropHovered(true)),
+        Event::Window(_, window::Event::FilesHoveredLeft) => Some(Message::DropHovered(false)),
+        Event::Window(_, window::Event::FileDropped(path)) => Some(Message::SourceDropped(path)),
+        _ => None,
+    }
+}
+
+fn column_resize_event(event: Event, _status: event::Status) -> Option<Message> {
+    match event {
+        Event::Mouse(mouse::Event::CursorMoved { position }) => {
+            Some(Message::ColumnResizeMoved(position.x))
+        }
+        Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+            Some(Message::ColumnResizeEnded)
+        }
+        _ => None,
+    }
+}
+
+/// Search box, sort order and quick filters of the student list.
+fn student_list_controls(state: &AppState) -> Element<'_, Message> {
+    let toggle = |label: String, active: bool, message| {
+        button(text(label).size(12))
+            .on_press(message)
+            .style(if active {
+                theme::Button::Custom(Box::new(style::PrimaryButton))
+            } else {
+                theme::Button::Custom(Box::new(style::Button))
+            })
+            .padding([4, 8])
+    };
+    let shown = state.visible_students().len();
+    let total = state
+        .report
+        .as_ref()
+        .map_or(0, |report| report.students.len());
+
+    let filters = StudentFilter::ALL.iter().fold(
+        row![].spacing(6).align_items(Alignment::Center),
+        |filters, &filter| {
+            filters.push(toggle(
+                filter.to_string(),
+                state.student_filter == Some(filter),
+                Message::StudentFilterToggled(filter),
+            ))
+        },
+    );
+    column![
+        text_input("Search name, ID or email...", &state.student_query)
+            .on_input(Message::StudentSearchChanged)
+            .style(theme::TextInput::Custom(Box::new(style::TextInput)))
+            .padding(6)
+            .size(14),
+        row![
+            text("Sort").size(12).style(style::BASE00),
+            pick_list(
+                StudentSort::ALL,
+                Some(state.student_sort),
+                Message::StudentSortChanged
+            )
+            .text_size(12)
+            .style(theme::PickList::Custom(
+                std::rc::Rc::new(style::PickList),
+                std::rc::Rc::new(style::Menu)
+            )),
+            toggle(
+                if state.sort_descending { "DESC" } else { "ASC" }.to_string(),
+                false,
+                Message::SortDirectionToggled
+            ),
+            Space::with_width(Length::Fill),
+            text(format!("{shown}/{total}"))
+                .size(12)
+                .style(style::BASE00),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center),
+        filters,
+    ]
+    .spacing(6)
+    .into()
+}
+
+fn student_detail_view<'a>(
+    report: &'a AttendanceReport,
     selected_student: Option<usize>,
-) -> Element<'_, Message> {
+    draft: Option<&'a OverrideDraft>,
+) -> Element<'a, Message> {
     if let Some(index) = selected_student
         && let Some(student) = report.students.get(index)
     {
@@ -446,7 +1548,8 @@ fn student_detail_view(
             ))
             .style(theme::Scrollable::Custom(Box::new(style::Scrollable)));
 
-        let pie = Canvas::new(PieChart::new(student))
+        let pie_chart = charts::student_pie(student);
+        let pie = Canvas::new(PieChart::new(&pie_chart))
             .width(Length::Fill)
             .height(Length::Fixed(240.0));
 
@@ -468,18 +1571,35 @@ fn student_detail_view(
         .spacing(16)
         .align_items(Alignment::Center);
 
-        return column![
+        let timeline = Canvas::new(timeline::TimelineChart::new(report, student))
+            .width(Length::Fill)
+            .height(Length::Fixed(160.0));
+
+        let draft = draft.filter(|draft| draft.student == index);
+        let details = column![
             text("SELECTED STUDENT").size(14).style(style::BASE1),
             Space::with_height(Length::Fixed(10.0)),
             table_scrollable,
             Space::with_height(Length::Fixed(20.0)),
-            text("ATTENDANCE DISTRIBUTION").size(14).style(style::BASE1),
+            dashboard::chart_title("Attendance distribution", ChartType::Pie),
             pie,
             container(legend).width(Length::Fill).center_x(),
+            Space::with_height(Length::Fixed(20.0)),
+            dashboard::chart_title("Session timeline", ChartType::Timeline),
+            timeline,
+            text("Bar height shows minutes late; faint bars are missed sessions.")
+                .size(12)
+                .style(style::BASE01),
+            Space::with_height(Length::Fixed(20.0)),
+            text("SESSIONS").size(14).style(style::BASE1),
+            session_history_view(student, draft),
         ]
         .spacing(12)
-        .padding(16)
-        .into();
+        .padding(16);
+        return scrollable(details)
+            .height(Length::Fill)
+            .style(theme::Scrollable::Custom(Box::new(style::Scrollable)))
+            .into();
     }
 
     container(
@@ -492,37 +1612,330 @@ fn student_detail_view(
     .into()
 }
 
-async fn pick_directory() -> Option<PathBuf> {
-    rfd::FileDialog::new().pick_folder()
+/// Kind of a source as shown in the source list.
+fn source_kind(path: &Path) -> &'static str {
+    if path.is_dir() {
+        "FOLDER"
+    } else if presence_core::archive::is_archive(path) {
+        "ZIP"
+    } else if path.is_file() {
+        "FILE"
+    } else {
+        "MISSING"
+    }
 }
 
-async fn pick_file() -> Option<PathBuf> {
-    rfd::FileDialog::new().pick_file()
+/// The source list: every folder and file with a checkbox that includes it
+/// in the analysis, and a button removing it.
+fn source_list_view(sources: &[SourceEntry]) -> Element<'_, Message> {
+    sources
+        .iter()
+        .enumerate()
+        .fold(Column::new().spacing(4), |list, (index, source)| {
+            let kind = source_kind(&source.path);
+            let kind_color = if kind == "MISSING" {
+                style::RED
+            } else {
+                style::BASE00
+            };
+            let path_color = if source.enabled {
+                style::BASE0
+            } else {
+                style::BASE01
+            };
+            list.push(
+                row![
+                    checkbox("", source.enabled)
+                        .on_toggle(move |enabled| Message::SourceToggled(index, enabled))
+                        .style(theme::Checkbox::Custom(Box::new(style::Checkbox)))
+                        .size(16)
+                        .width(Length::Shrink),
+                    container(text(kind).size(11).style(kind_color)).width(Length::Fixed(56.0)),
+                    text(source.path.display().to_string())
+                        .size(13)
+                        .style(path_color)
+                        .width(Length::Fill),
+                    button(text("REMOVE").size(12))
+                        .on_press(Message::RemoveSource(index))
+                        .style(theme::Button::Custom(Box::new(style::Button)))
+                        .padding([4, 8]),
+                ]
+                .spacing(8)
+                .align_items(Alignment::Center),
+            )
+        })
+        .into()
 }
 
-async fn load_attendance(
-    directory: PathBuf,
-    config: core::AttendanceConfig,
-) -> Result<AttendanceReport, String> {
-    state::load_attendance(directory, config)
+/// Path of a scanned export as listed for review: relative to its source
+/// folder, or starting with the folder's name when there are several sources.
+fn source_relative<'a>(sources: &[PathBuf], file: &'a Path) -> &'a Path {
+    let Some(source) = sources.iter().find(|source| file.starts_with(source)) else {
+        return file;
+    };
+    let base = if sources.len() > 1 || source == file {
+        source.parent().unwrap_or(source)
+    } else {
+        source
+    };
+    file.strip_prefix(base).unwrap_or(file)
 }
 
-async fn save_report(report: AttendanceReport, format: ReportFormat) -> Result<PathBuf, String> {
-    state::save_report(report, format)
+/// Export columns with a tick to include each, its header and buttons to
+/// reorder it. Ticked columns are exported from top to bottom.
+fn column_editor(choices: &[ColumnChoice]) -> Element<'_, Message> {
+    let last = choices.len().saturating_sub(1);
+    let rows = choices
+        .iter()
+        .enumerate()
+        .fold(Column::new().spacing(4), |col, (index, choice)| {
+            let move_button = |label: &'static str, step: isize, enabled: bool| {
+                button(text(label).size(12))
+                    .on_press_maybe(enabled.then_some(Message::MoveExportColumn(index, step)))
+                    .style(theme::Button::Custom(Box::new(style::Button)))
+                    .padding([2, 8])
+            };
+            col.push(
+                row![
+                    checkbox(choice.column.key(), choice.enabled)
+                        .on_toggle(move |enabled| Message::ExportColumnToggled(index, enabled))
+                        .style(theme::Checkbox::Custom(Box::new(style::Checkbox)))
+                        .text_size(14)
+                        .size(16)
+                        .width(Length::Fixed(140.0)),
+                    text_input(choice.column.default_label(), &choice.label)
+                        .on_input(move |label| Message::ExportColumnLabelChanged(index, label))
+                        .style(theme::TextInput::Custom(Box::new(style::TextInput)))
+                        .padding(4)
+                        .size(14)
+                        .width(Length::Fixed(220.0)),
+                    move_button("UP", -1, index > 0),
+                    move_button("DOWN", 1, index < last),
+                ]
+                .spacing(8)
+                .align_items(Alignment::Center),
+            )
+        });
+    container(
+        scrollable(container(rows).padding(8).width(Length::Fill))
+            .height(Length::Fixed(180.0))
+            .style(theme::Scrollable::Custom(Box::new(style::Scrollable))),
+    )
+    .style(theme::Container::Custom(Box::new(style::InputGroup)))
+    .width(Length::Fill)
+    .into()
+}
+
+/// Lists the exports found by a scan so they can be checked before analysis.
+fn discovered_view<'a>(
+    sources: &[PathBuf],
+    files: &[PathBuf],
+    config_valid: bool,
+) -> Element<'a, Message> {
+    let list = files.iter().fold(Column::new().spacing(2), |col, file| {
+        let relative = source_relative(sources, file);
+        col.push(
+            text(relative.display().to_string())
+                .size(14)
+                .style(style::BASE0),
+        )
+    });
+    let list = scrollable(container(list).padding(8).width(Length::Fill))
+        .height(Length::Fill)
+        .style(theme::Scrollable::Custom(Box::new(style::Scrollable)));
+
+    container(
+        column![
+            row![
+                text(format!("FOUND {} FILES", files.len()))
+                    .size(14)
+                    .style(style::BASE1),
+                Space::with_width(Length::Fill),
+                button(text("CANCEL").size(14))
+                    .on_press(Message::CancelScan)
+                    .style(theme::Button::Custom(Box::new(style::Button)))
+                    .padding(8),
+                button(text(format!("ANALYZE {} FILES", files.len())).size(14))
+                    .on_press_maybe(config_valid.then_some(Message::ConfirmAnalysis))
+                    .style(theme::Button::Custom(Box::new(style::PrimaryButton)))
+                    .padding(8),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+            Space::with_height(Length::Fixed(10.0)),
+            list,
+        ]
+        .padding(16),
+    )
+    .style(theme::Container::Custom(Box::new(style::Panel)))
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
+
+/// One row per session with its status and an editor for manual overrides.
+/// Overridden statuses are marked with `*` and show the computed status.
+fn session_history_view<'a>(
+    student: &'a StudentRecord,
+    draft: Option<&'a OverrideDraft>,
+) -> Element<'a, Message> {
+    let small_button = |label, message: Option<Message>| {
+        button(text(label).size(12))
+            .on_press_maybe(message)
+            .style(theme::Button::Custom(Box::new(style::Button)))
+            .padding([4, 8])
+    };
+    let status_text = |session: &SessionAttendance| match session.original {
+        Some(original) => {
+            text(format!("{}* (was {original})", session.status)).style(style::MAGENTA)
+        }
+        None => text(session.status.to_string()).style(style::status_color(session.status)),
+    };
+
+    student
+        .history
+        .iter()
+        .enumerate()
+        .fold(Column::new().spacing(4), |col, (index, session)| {
+            let join = session
+                .first_join
+                .map(|join| join.format("%H:%M").to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let row = row![
+                text(format!("{:>3}", index + 1))
+                    .size(14)
+                    .style(style::BASE00),
+                text(session.date.format("%Y-%m-%d").to_string()).size(14),
+                text(join).size(14).width(Length::Fixed(60.0)),
+                status_text(session).size(14).width(Length::Fill),
+                small_button("OVERRIDE", Some(Message::EditOverride(index))),
+            ]
+            .spacing(12)
+            .align_items(Alignment::Center);
+            let col = col.push(row);
+            match draft {
+                Some(draft) if draft.session == index => {
+                    let reason = draft.reason.trim();
+                    col.push(
+                        container(
+                            row![
+                                pick_list(
+                                    AttendanceStatus::ALL,
+                                    Some(draft.status),
+                                    Message::OverrideStatusChanged
+                                )
+                                .text_size(12)
+                                .style(theme::PickList::Custom(
+                                    std::rc::Rc::new(style::PickList),
+                                    std::rc::Rc::new(style::Menu)
+                                )),
+                                text_input("Reason (required)", &draft.reason)
+                                    .on_input(Message::OverrideReasonChanged)
+                                    .on_submit(Message::SaveOverride)
+                                    .style(theme::TextInput::Custom(Box::new(style::TextInput)))
+                                    .padding(4)
+                                    .size(12)
+                                    .width(Length::Fill),
+                                small_button(
+                                    "SAVE",
+                                    (!reason.is_empty()).then_some(Message::SaveOverride)
+                                ),
+                                small_button(
+                                    "REMOVE",
+                                    session.original.map(|_| Message::RemoveOverride)
+                                ),
+                                small_button("CANCEL", Some(Message::CancelOverride)),
+                            ]
+                            .spacing(8)
+                            .align_items(Alignment::Center),
+                        )
+                        .style(theme::Container::Custom(Box::new(style::BorderedPanel)))
+                        .padding(6),
+                    )
+                }
+                _ => col,
+            }
+        })
+        .into()
+}
+
+async fn pick_audit_log_destination(file_name: String) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("CSV", &["csv"])
+        .set_file_name(file_name)
+        .save_file()
+}
+
+async fn pick_chart_destination(file_name: String) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("SVG image", &["svg"])
+        .add_filter("PNG image", &["png"])
+        .set_file_name(file_name)
+        .save_file()
+}
+
+async fn pick_directories() -> Option<Vec<PathBuf>> {
+    rfd::FileDialog::new().pick_folders()
+}
+
+async fn pick_files() -> Option<Vec<PathBuf>> {
+    rfd::FileDialog::new().pick_files()
+}
+
+async fn pick_profile() -> Option<PathBuf> {
+    let mut dialog = rfd::FileDialog::new().add_filter("Profile", &["toml", "json"]);
+    if let Some(dir) = profile::config_dir().filter(|dir| dir.is_dir()) {
+        dialog = dialog.set_directory(dir);
+    }
+    dialog.pick_file()
+}
+
+async fn pick_profile_destination(file_name: String) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Profile", &["toml", "json"])
+        .set_file_name(file_name)
+        .save_file()
+}
+
+async fn pick_project() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Presence project", &[project::PROJECT_EXTENSION])
+        .pick_file()
+}
+
+async fn pick_project_destination(file_name: String) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Presence project", &[project::PROJECT_EXTENSION])
+        .set_file_name(file_name)
+        .save_file()
+}
+
+async fn save_report(
+    report: AttendanceReport,
+    format: ReportFormat,
+    options: ExportOptions,
+) -> Result<PathBuf, String> {
+    state::save_report(report, format, options).map_err(|error| state::user_message(&error))
+}
+
+async fn save_all_reports(
+    report: AttendanceReport,
+    options: ExportOptions,
+) -> Result<Vec<PathBuf>, String> {
+    state::save_all_reports(report, options).map_err(|error| state::user_message(&error))
 }
 
 struct PieChart {
-    normal: u32,
-    late: u32,
-    absent: u32,
+    slices: Vec<Mark>,
 }
 
 impl PieChart {
-    fn new(student: &StudentRecord) -> Self {
-        Self {
-            normal: student.normal,
-            late: student.late,
-            absent: student.absent,
+    fn new(chart: &Chart) -> Self {
+        match &chart.plot {
+            Plot::Pie(slices) => Self {
+                slices: slices.clone(),
+            },
+            _ => Self { slices: Vec::new() },
         }
     }
 }
@@ -539,17 +1952,13 @@ impl canvas::Program<Message> for PieChart {
         _cursor: mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
-        let total = (self.normal + self.late + self.absent) as f32;
+        let total: f32 = self.slices.iter().map(|slice| slice.value).sum();
         if total > 0.0 {
             let center = Point::new(bounds.width / 2.0, bounds.height / 2.0);
             let radius = bounds.width.min(bounds.height) * 0.35;
             let mut start_angle = 0.0;
-            for (value, color) in [
-                (self.normal, style::CHART_GREEN),
-                (self.late, style::CHART_YELLOW),
-                (self.absent, style::CHART_RED),
-            ] {
-                let sweep = (value as f32 / total) * std::f32::consts::TAU;
+            for slice in &self.slices {
+                let sweep = (slice.value / total) * std::f32::consts::TAU;
                 if sweep > 0.0 {
                     let arc = canvas::path::Arc {
                         center,
@@ -567,7 +1976,7 @@ impl canvas::Program<Message> for PieChart {
                         builder.line_to(center);
                         builder.close();
                     });
-                    frame.fill(&path, color);
+                    frame.fill(&path, style::tone_color(slice.tone));
                     frame.stroke(
                         &path,
                         canvas::Stroke::default()
@@ -1,24 +1,90 @@
 use std::path::PathBuf;
 
-use crate::core::{AttendanceReport, ReportFormat};
+use presence_core::{AttendanceReport, AttendanceStatus, ChartType, LoadProgress, ReportFormat};
+
+use crate::state::{RecentProject, StudentFilter, StudentSort, Tab};
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    DirectoryChanged(String),
+    SourceInputChanged(String),
+    SubmitSourceInput,
     PickDirectory,
-    DirectoryPicked(Option<PathBuf>),
     PickFile,
-    FilePicked(Option<PathBuf>),
+    SourcesPicked(Option<Vec<PathBuf>>),
+    RemoveSource(usize),
+    SourceToggled(usize, bool),
+    DropHovered(bool),
+    SourceDropped(PathBuf),
+    DropFinished(u64),
+    AnalyzeOnDropToggled(bool),
     ClassStartChanged(String),
     ClassEndChanged(String),
     LateMinutesChanged(String),
     AbsentMinutesChanged(String),
     TotalPointsChanged(String),
     LatePenaltyChanged(String),
+    MinAttendanceChanged(String),
+    ColumnsEditorToggled,
+    ExportColumnToggled(usize, bool),
+    ExportColumnLabelChanged(usize, String),
+    MoveExportColumn(usize, isize),
+    GradeItemChanged(String),
+    CourseNameChanged(String),
+    TermChanged(String),
+    StudentPagesToggled(bool),
     ReportFormatChanged(ReportFormat),
+    RecursiveToggled(bool),
+    IncludeChanged(String),
+    ExcludeChanged(String),
+    ScanSource,
+    ConfirmAnalysis,
+    CancelScan,
     RunAnalysis,
+    AnalysisProgress(LoadProgress),
     AnalysisDone(Result<AttendanceReport, String>),
+    CancelAnalysis,
+    AnalysisCancelled,
+    TabSelected(Tab),
     SelectStudent(usize),
+    ClearStudentSelection,
+    SelectSession(usize),
+    CloseSession,
+    StudentSearchChanged(String),
+    StudentSortChanged(StudentSort),
+    SortDirectionToggled,
+    StudentFilterToggled(StudentFilter),
+    TableViewToggled,
+    ColumnResizeStarted(usize),
+    ColumnResizeMoved(f32),
+    ColumnResizeEnded,
+    MoveSelection(isize),
+    EditOverride(usize),
+    OverrideStatusChanged(AttendanceStatus),
+    OverrideReasonChanged(String),
+    SaveOverride,
+    RemoveOverride,
+    CancelOverride,
+    ExportAuditLog,
+    AuditLogDestinationPicked(Option<PathBuf>),
+    ChartSizeChanged(String),
+    ExportChart(ChartType),
+    ChartDestinationPicked(ChartType, Option<PathBuf>),
     ExportReport,
     ReportSaved(Result<PathBuf, String>),
+    ExportAll,
+    ReportsSaved(Result<Vec<PathBuf>, String>),
+    SaveProfile,
+    LoadProfile,
+    ProfilePicked(Option<PathBuf>),
+    DuplicateProfile,
+    ProfileDestinationPicked(Option<PathBuf>),
+    OpenProject,
+    ProjectPicked(Option<PathBuf>),
+    RecentProjectSelected(RecentProject),
+    SaveProject,
+    ProjectDestinationPicked(Option<PathBuf>),
+    NotesChanged(String),
+    WatchToggled(bool),
+    SourceChanged,
+    WatchFailed(String),
 }
@@ -0,0 +1,404 @@
+use std::path::Path;
+
+use iced::widget::{Column, Space, button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Length, theme};
+use presence_core::{AttendanceReport, AttendanceStatus, summarize};
+
+use crate::messages::Message;
+use crate::state::AppState;
+use crate::style;
+
+const NUMBER_WIDTH: f32 = 36.0;
+const DATE_WIDTH: f32 = 100.0;
+const TITLE_WIDTH: f32 = 140.0;
+const FILE_WIDTH: f32 = 200.0;
+const COUNT_WIDTH: f32 = 80.0;
+const NAME_WIDTH: f32 = 140.0;
+const ID_WIDTH: f32 = 100.0;
+const EMAIL_WIDTH: f32 = 220.0;
+const TIME_WIDTH: f32 = 70.0;
+
+/// File name of an export, including the archive it came from.
+pub fn source_label(path: &Path) -> Option<String> {
+    let file = path.file_name()?.to_string_lossy();
+    let archive = path
+        .ancestors()
+        .skip(1)
+        .find(|ancestor| presence_core::archive::is_archive(ancestor))
+        .and_then(|archive| archive.file_name());
+    Some(match archive {
+        Some(archive) => format!("{}: {file}", archive.to_string_lossy()),
+        None => file.into_owned(),
+    })
+}
+
+/// Title of a session: the export's file name without extension, with
+/// separators turned into spaces (`week-03.csv` is "week 03").
+pub fn session_title(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().replace(['-', '_'], " "))
+        .unwrap_or_default()
+}
+
+/// Who was on time, late and absent in one session of the report.
+pub fn session_detail_view(report: &AttendanceReport, session: usize) -> Element<'_, Message> {
+    let Some(date) = report.session_dates.get(session) else {
+        return text("Session not found.").style(style::BASE01).into();
+    };
+    let source = report
+        .session_sources
+        .get(session)
+        .map(|source| source.display().to_string())
+        .unwrap_or_default();
+
+    let mut groups = AttendanceStatus::ALL.map(|status| (status, Vec::new()));
+    for student in &report.students {
+        let Some(attendance) = student.history.get(session) else {
+            continue;
+        };
+        let join = match attendance.first_join {
+            Some(join) if attendance.minutes_late > 0 => {
+                format!("{} (+{})", join.format("%H:%M"), attendance.minutes_late)
+            }
+            Some(join) => join.format("%H:%M").to_string(),
+            None => String::new(),
+        };
+        let marker = if attendance.original.is_some() {
+            "*"
+        } else {
+            ""
+        };
+        let (_, names) = groups
+            .iter_mut()
+            .find(|(status, _)| *status == attendance.status)
+            .expect("every status has a group");
+        names.push((
+            format!("{}, {}{marker}", student.surname, student.name),
+            join,
+        ));
+    }
+    let present = groups
+        .iter()
+        .filter(|(status, _)| *status != AttendanceStatus::Absent)
+        .map(|(_, names)| names.len())
+        .sum::<usize>();
+    let total = report.students.len().max(1);
+
+    let lists = groups.into_iter().fold(
+        row![].spacing(16).align_items(Alignment::Start),
+        |lists, (status, mut names)| {
+            names.sort();
+            let label = match status {
+                AttendanceStatus::Normal => "ON TIME",
+                AttendanceStatus::Late => "LATE",
+                AttendanceStatus::Absent => "ABSENT",
+            };
+            let heading = text(format!("{label} ({})", names.len()))
+                .size(14)
+                .style(style::status_color(status));
+            let list = names.into_iter().fold(
+                Column::new().spacing(2).push(heading),
+                |list, (name, join)| {
+                    list.push(
+                        row![
+                            text(name).size(13).width(Length::Fill),
+                            text(join).size(13).style(style::BASE00),
+                        ]
+                        .spacing(8),
+                    )
+                },
+            );
+            lists.push(list.width(Length::Fill))
+        },
+    );
+
+    column![
+        row![
+            text(format!(
+                "SESSION {} \u{00b7} {}",
+                session + 1,
+                date.format("%a %Y-%m-%d")
+            ))
+            .size(14)
+            .style(style::BASE1),
+            Space::with_width(Length::Fill),
+            button(text("CLOSE").size(12))
+                .on_press(Message::CloseSession)
+                .style(theme::Button::Custom(Box::new(style::Button)))
+                .padding([4, 8]),
+        ]
+        .align_items(Alignment::Center),
+        text(source).size(12).style(style::BASE00),
+    ]
+    .push_maybe(same_day_sessions(report, session))
+    .push(
+        text(format!(
+            "Present {present}/{} ({:.1}%)",
+            report.students.len(),
+            present as f32 / total as f32 * 100.0
+        ))
+        .size(12)
+        .style(style::BASE0),
+    )
+    .push(lists)
+    .spacing(8)
+    .into()
+}
+
+/// Buttons for every session held on the same day as `session`, when there
+/// is more than one, so each can be opened from the calendar.
+fn same_day_sessions(report: &AttendanceReport, session: usize) -> Option<Element<'_, Message>> {
+    let date = report.session_dates.get(session)?;
+    let sessions: Vec<usize> = (0..report.session_dates.len())
+        .filter(|&index| report.session_dates[index] == *date)
+        .collect();
+    if sessions.len() < 2 {
+        return None;
+    }
+    let buttons = sessions.into_iter().fold(
+        row![text("THIS DAY").size(12).style(style::BASE00)]
+            .spacing(8)
+            .align_items(Alignment::Center),
+        |buttons, index| {
+            let title = report
+                .session_sources
+                .get(index)
+                .map(|source| session_title(source))
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| format!("Session {}", index + 1));
+            buttons.push(
+                button(text(title).size(12))
+                    .on_press(Message::SelectSession(index))
+                    .style(if index == session {
+                        theme::Button::Custom(Box::new(style::PrimaryButton))
+                    } else {
+                        theme::Button::Custom(Box::new(style::Button))
+                    })
+                    .padding([4, 8]),
+            )
+        },
+    );
+    Some(buttons.into())
+}
+
+/// Every session of the report with its export, participant count and
+/// attendance rate. Selecting one lists its participants next to it.
+pub fn sessions_view<'a>(state: &AppState, report: &'a AttendanceReport) -> Element<'a, Message> {
+    let summary = summarize(report);
+    let cell = |content: String, width: f32| {
+        container(text(content).size(14))
+            .padding([4, 6])
+            .width(Length::Fixed(width))
+    };
+    let header = row![
+        cell("#".into(), NUMBER_WIDTH),
+        cell("Date".into(), DATE_WIDTH),
+        cell("Title".into(), TITLE_WIDTH),
+        cell("File".into(), FILE_WIDTH),
+        cell("Joined".into(), COUNT_WIDTH),
+        cell("Rate".into(), COUNT_WIDTH),
+    ];
+
+    let rows = report.session_dates.iter().enumerate().fold(
+        Column::new().spacing(2),
+        |rows, (index, date)| {
+            let source = report.session_sources.get(index);
+            let joined = report
+                .students
+                .iter()
+                .filter(|student| {
+                    student
+                        .history
+                        .get(index)
+                        .is_some_and(|session| session.first_join.is_some())
+                })
+                .count();
+            let rate = summary.session_rates.get(index).copied().unwrap_or(0.0);
+            let cells = row![
+                cell((index + 1).to_string(), NUMBER_WIDTH),
+                cell(date.format("%Y-%m-%d").to_string(), DATE_WIDTH),
+                cell(
+                    source
+                        .map(|source| session_title(source))
+                        .unwrap_or_default(),
+                    TITLE_WIDTH
+                ),
+                cell(
+                    source
+                        .and_then(|source| source_label(source))
+                        .unwrap_or_default(),
+                    FILE_WIDTH
+                ),
+                cell(joined.to_string(), COUNT_WIDTH),
+                cell(format!("{:.1}%", rate * 100.0), COUNT_WIDTH),
+            ];
+            let style = if state.selected_session == Some(index) {
+                theme::Button::Custom(Box::new(style::PrimaryButton))
+            } else {
+                theme::Button::Custom(Box::new(style::Button))
+            };
+            rows.push(
+                button(cells)
+                    .on_press(Message::SelectSession(index))
+                    .style(style)
+                    .padding(0),
+            )
+        },
+    );
+
+    let list = column![
+        text(format!("SESSIONS ({})", report.sessions))
+            .size(14)
+            .style(style::BASE1),
+        container(header).style(theme::Container::Custom(Box::new(style::BorderedPanel))),
+        scrollable(rows)
+            .height(Length::Fill)
+            .style(theme::Scrollable::Custom(Box::new(style::Scrollable))),
+    ]
+    .spacing(8)
+    .padding(16);
+
+    let participants: Element<Message> = match state.selected_session {
+        Some(session) if session < report.sessions => participant_table(report, session),
+        _ => container(text("Select a session to list its participants.").style(style::BASE01))
+            .center_x()
+            .center_y()
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into(),
+    };
+
+    row![
+        container(list)
+            .style(theme::Container::Custom(Box::new(style::Panel)))
+            .height(Length::Fill),
+        container(participants)
+            .style(theme::Container::Custom(Box::new(style::Panel)))
+            .width(Length::Fill)
+            .height(Length::Fill),
+    ]
+    .spacing(16)
+    .into()
+}
+
+/// Every student of the report in one session, in the order they joined and
+/// the absent ones last, as the export was read.
+fn participant_table(report: &AttendanceReport, session: usize) -> Element<'_, Message> {
+    let cell = |content: String, width: f32| {
+        container(text(content).size(14))
+            .padding([4, 6])
+            .width(Length::Fixed(width))
+    };
+    let mut attendance: Vec<_> = report
+        .students
+        .iter()
+        .filter_map(|student| Some((student, student.history.get(session)?)))
+        .collect();
+    attendance.sort_by(|(a, a_session), (b, b_session)| {
+        match (a_session.first_join, b_session.first_join) {
+            (Some(a_join), Some(b_join)) => a_join.cmp(&b_join),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => (&a.surname, &a.name).cmp(&(&b.surname, &b.name)),
+        }
+    });
+
+    let header = row![
+        cell("Surname".into(), NAME_WIDTH),
+        cell("Name".into(), NAME_WIDTH),
+        cell("ID".into(), ID_WIDTH),
+        cell("Email".into(), EMAIL_WIDTH),
+        cell("Joined".into(), TIME_WIDTH),
+        cell("Late".into(), TIME_WIDTH),
+        cell("Status".into(), COUNT_WIDTH),
+    ];
+    let rows =
+        attendance
+            .into_iter()
+            .fold(Column::new().spacing(2), |rows, (student, attendance)| {
+                let status = match attendance.original {
+                    Some(original) => format!("{}* (was {original})", attendance.status),
+                    None => attendance.status.to_string(),
+                };
+                let color = if attendance.original.is_some() {
+                    style::MAGENTA
+                } else {
+                    style::status_color(attendance.status)
+                };
+                rows.push(row![
+                    cell(student.surname.clone(), NAME_WIDTH),
+                    cell(student.name.clone(), NAME_WIDTH),
+                    cell(student.id.clone(), ID_WIDTH),
+                    cell(student.email.clone(), EMAIL_WIDTH),
+                    cell(
+                        attendance
+                            .first_join
+                            .map(|join| join.format("%H:%M:%S").to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                        TIME_WIDTH
+                    ),
+                    cell(
+                        if attendance.first_join.is_some() {
+                            format!("{} min", attendance.minutes_late)
+                        } else {
+                            "-".to_string()
+                        },
+                        TIME_WIDTH
+                    ),
+                    container(text(status).size(14).style(color)).padding([4, 6]),
+                ])
+            });
+
+    let source = report
+        .session_sources
+        .get(session)
+        .map(|source| source.display().to_string())
+        .unwrap_or_default();
+    column![
+        text(format!(
+            "PARTICIPANTS \u{00b7} SESSION {} \u{00b7} {}",
+            session + 1,
+            report.session_dates[session].format("%a %Y-%m-%d")
+        ))
+        .size(14)
+        .style(style::BASE1),
+        text(source).size(12).style(style::BASE00),
+        scrollable(
+            column![
+                container(header).style(theme::Container::Custom(Box::new(style::BorderedPanel))),
+                rows,
+            ]
+            .spacing(4)
+        )
+        .direction(scrollable::Direction::Both {
+            vertical: scrollable::Properties::new(),
+            horizontal: scrollable::Properties::new(),
+        })
+        .height(Length::Fill)
+        .style(theme::Scrollable::Custom(Box::new(style::Scrollable))),
+    ]
+    .spacing(8)
+    .padding(16)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_label_names_archive() {
+        assert_eq!(
+            source_label(Path::new("/exports/week-01.csv")).as_deref(),
+            Some("week-01.csv")
+        );
+        assert_eq!(
+            source_label(Path::new("/exports/bulk.zip/week-02/session.csv")).as_deref(),
+            Some("bulk.zip: session.csv")
+        );
+        assert_eq!(
+            session_title(Path::new("/exports/week_01-intro.xlsx")),
+            "week 01 intro"
+        );
+    }
+}
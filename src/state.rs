@@ -1,46 +1,581 @@
-use std::fs::File;
-use std::io::Write;
-use std::path::{Path, PathBuf};
-
-use genpdf::elements::PaddedElement;
-use genpdf::{
-    Element,
-    style::{Color, Style},
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use presence_core::columns::{ColumnSpec, ExportColumn};
+use presence_core::overrides::{self, StatusOverride};
+use presence_core::profile::Profile;
+use presence_core::project::{self, Project, RosterEntry};
+use presence_core::watch::Snapshot;
+use presence_core::{
+    AttendanceConfig, AttendanceReport, AttendanceStatus, CancelToken, ChartSize, ExportOptions,
+    LoadProgress, PresenceError, ReportFormat, ScanOptions, SessionCache, StudentRecord, columns,
+    export, parse_config, validate_config,
 };
 
-use crate::core::{self, AttendanceConfig, AttendanceReport, Participant, ReportFormat};
+use crate::table;
 
 #[derive(Debug, Clone)]
 pub struct AppState {
-    pub directory: String,
+    /// Folders, exports and archives; the enabled ones are analyzed together
+    /// into one report.
+    pub sources: Vec<SourceEntry>,
+    /// Path typed into the source field, added on Enter.
+    pub source_input: String,
+    /// Analyze as soon as files or folders are dropped onto the window.
+    pub analyze_on_drop: bool,
+    /// Files are being dragged over the window.
+    pub drop_hovering: bool,
+    /// Number of files dropped so far. Every file of a drop arrives as its
+    /// own event; only the last one starts the analysis.
+    pub drop_batch: u64,
+    /// Sources added by the files of the current drop.
+    pub dropped_sources: usize,
+    pub recursive: bool,
+    /// Comma-separated glob patterns of exports to analyze.
+    pub include: String,
+    /// Comma-separated glob patterns of exports to skip.
+    pub exclude: String,
+    /// Files found by the last scan, waiting for the user to confirm them.
+    pub discovered: Option<Vec<PathBuf>>,
     pub class_start: String,
     pub class_end: String,
     pub late_minutes: String,
     pub absent_minutes: String,
     pub total_points: String,
     pub late_penalty: String,
+    pub min_attendance: String,
+    /// Problems with the analysis settings, by field, refreshed on every edit.
+    pub config_errors: Vec<(&'static str, String)>,
+    /// Every export column, the selected ones first in export order.
+    pub export_columns: Vec<ColumnChoice>,
+    /// The export column editor is expanded.
+    pub columns_open: bool,
+    pub grade_item: String,
+    pub course_name: String,
+    pub term: String,
+    pub student_pages: bool,
+    /// Size of exported charts, `WIDTHxHEIGHT` in pixels.
+    pub chart_size: String,
+    /// Profile file that SAVE writes to; `None` until one is loaded or saved.
+    pub profile_path: Option<PathBuf>,
+    /// Project file the session was opened from or last saved to.
+    pub project_path: Option<PathBuf>,
+    pub notes: String,
+    /// Students of the last analysis, or of the opened project until one runs.
+    pub roster: Vec<RosterEntry>,
+    pub recent_projects: Vec<RecentProject>,
+    /// Manual status corrections, laid over every report.
+    pub overrides: Vec<StatusOverride>,
+    /// Override being edited in the student detail view.
+    pub override_draft: Option<OverrideDraft>,
     pub report_format: ReportFormat,
     pub report: Option<AttendanceReport>,
+    /// Parsed exports shared with the analysis task, so re-running only
+    /// re-parses files that changed.
+    pub session_cache: Arc<Mutex<SessionCache>>,
+    /// Re-analyze automatically when exports in the source change.
+    pub watching: bool,
+    /// Source files as they were when the last analysis started.
+    pub snapshot: Snapshot,
+    /// Description of the changes that triggered the running analysis.
+    pub change_notice: Option<String>,
+    /// The source changed while an analysis was running.
+    pub reanalyze_pending: bool,
+    /// The running analysis, if any.
+    pub job: Option<LoadJob>,
+    /// Id given to the next analysis job.
+    pub next_job: u64,
+    /// Files parsed so far by the running analysis.
+    pub progress: Option<LoadProgress>,
+    /// Index into `report.students`, independent of how the list is sorted.
     pub selected_student: Option<usize>,
+    /// Text typed into the student search box.
+    pub student_query: String,
+    pub student_sort: StudentSort,
+    pub sort_descending: bool,
+    pub student_filter: Option<StudentFilter>,
+    pub tab: Tab,
+    /// Index into `report.session_dates` of the session shown in detail.
+    pub selected_session: Option<usize>,
+    /// Show the whole class as a table instead of the list and detail panels.
+    pub table_view: bool,
+    /// Width of each column of `table::COLUMNS`.
+    pub column_widths: Vec<f32>,
+    /// Table column being resized with the mouse.
+    pub column_drag: Option<ColumnDrag>,
     pub status: String,
     pub is_busy: bool,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        Self {
-            directory: String::new(),
-            class_start: "13:30".to_string(),
-            class_end: "15:00".to_string(),
-            late_minutes: "10".to_string(),
-            absent_minutes: "30".to_string(),
-            total_points: "10".to_string(),
-            late_penalty: "0.5".to_string(),
+        let mut state = Self {
+            sources: Vec::new(),
+            source_input: String::new(),
+            analyze_on_drop: false,
+            drop_hovering: false,
+            drop_batch: 0,
+            dropped_sources: 0,
+            recursive: false,
+            include: String::new(),
+            exclude: String::new(),
+            discovered: None,
+            class_start: String::new(),
+            class_end: String::new(),
+            late_minutes: String::new(),
+            absent_minutes: String::new(),
+            total_points: String::new(),
+            late_penalty: String::new(),
+            min_attendance: String::new(),
+            config_errors: Vec::new(),
+            export_columns: Vec::new(),
+            columns_open: false,
+            grade_item: String::new(),
+            course_name: String::new(),
+            term: String::new(),
+            student_pages: false,
+            chart_size: ChartSize::default().to_string(),
+            profile_path: None,
+            project_path: None,
+            notes: String::new(),
+            roster: Vec::new(),
+            recent_projects: Vec::new(),
+            overrides: Vec::new(),
+            override_draft: None,
             report_format: ReportFormat::Csv,
             report: None,
+            session_cache: Arc::default(),
+            watching: false,
+            snapshot: Snapshot::default(),
+            change_notice: None,
+            reanalyze_pending: false,
+            job: None,
+            next_job: 0,
+            progress: None,
             selected_student: None,
-            status: "Select a directory to begin.".to_string(),
+            student_query: String::new(),
+            student_sort: StudentSort::Name,
+            sort_descending: false,
+            student_filter: None,
+            tab: Tab::Students,
+            selected_session: None,
+            table_view: false,
+            column_widths: table::default_widths(),
+            column_drag: None,
+            status: "Add or drop a folder or file to begin.".to_string(),
             is_busy: false,
+        };
+        state.apply_profile(&Profile::default());
+        state
+    }
+
+    /// Replaces every configuration field with the values of a profile.
+    pub fn apply_profile(&mut self, profile: &Profile) {
+        let config = profile.to_config();
+        self.class_start = config.class_start;
+        self.class_end = config.class_end;
+        self.late_minutes = config.late_minutes;
+        self.absent_minutes = config.absent_minutes;
+        self.total_points = config.total_points;
+        self.late_penalty = config.late_penalty;
+        self.min_attendance = config.min_attendance;
+        self.validate_config();
+        let specs =
+            columns::parse_columns(&profile.columns).unwrap_or_else(|_| columns::default_columns());
+        self.export_columns = ColumnChoice::from_specs(&specs);
+        self.grade_item = profile.grade_item.clone();
+        self.course_name = profile.course_name.clone();
+        self.term = profile.term.clone();
+        self.student_pages = profile.student_pages;
+        self.recursive = profile.recursive;
+        self.include = profile.include.join(", ");
+        self.exclude = profile.exclude.join(", ");
+    }
+
+    /// Builds a profile from the input fields, rejecting values that would not analyze.
+    pub fn to_profile(&self) -> Result<Profile, PresenceError> {
+        let config = parse_config(self.to_config())?;
+        let options = self.to_export_options()?;
+        Ok(Profile {
+            class_start: config.class_start.format("%H:%M").to_string(),
+            class_end: config.class_end.format("%H:%M").to_string(),
+            late_minutes: config.late_minutes,
+            absent_minutes: config.absent_minutes,
+            total_points: config.total_points,
+            late_penalty: config.late_penalty,
+            min_attendance: self.min_attendance.trim().parse().unwrap_or_default(),
+            course_name: options.course_name,
+            term: options.term,
+            columns: columns::format_columns(&self.selected_columns()),
+            grade_item: options.grade_item,
+            student_pages: options.student_pages,
+            recursive: self.recursive,
+            include: ScanOptions::split_patterns(&self.include),
+            exclude: ScanOptions::split_patterns(&self.exclude),
+        })
+    }
+
+    pub fn to_scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            recursive: self.recursive,
+            include: ScanOptions::split_patterns(&self.include),
+            exclude: ScanOptions::split_patterns(&self.exclude),
+        }
+    }
+
+    /// Bundles the source, settings, roster and notes into a project.
+    pub fn to_project(&self) -> Result<Project, PresenceError> {
+        let roster = match &self.report {
+            Some(report) => report.students.iter().map(RosterEntry::from).collect(),
+            None => self.roster.clone(),
+        };
+        Ok(Project {
+            sources: self
+                .sources
+                .iter()
+                .map(|source| source.path.clone())
+                .collect(),
+            disabled_sources: self
+                .sources
+                .iter()
+                .filter(|source| !source.enabled)
+                .map(|source| source.path.clone())
+                .collect(),
+            notes: self.notes.clone(),
+            profile: self.to_profile()?,
+            roster,
+            overrides: self.overrides.clone(),
+        })
+    }
+
+    /// Restores a project, discarding the current report.
+    pub fn apply_project(&mut self, project: Project) {
+        self.sources = project
+            .sources
+            .iter()
+            .map(|path| SourceEntry {
+                path: path.clone(),
+                enabled: !project.disabled_sources.contains(path),
+            })
+            .collect();
+        self.discovered = None;
+        self.apply_profile(&project.profile);
+        self.notes = project.notes;
+        self.roster = project.roster;
+        self.overrides = project.overrides;
+        self.override_draft = None;
+        self.report = None;
+        self.selected_student = None;
+        self.selected_session = None;
+    }
+
+    /// Shows a new report, or none, keeping the selected student and session
+    /// when they are still part of it. Indices change when a re-analysis adds
+    /// or removes students or sessions, so both are looked up again.
+    pub fn set_report(&mut self, report: Option<AttendanceReport>) {
+        let previous = self.report.take();
+        let student = previous
+            .as_ref()
+            .zip(self.selected_student)
+            .and_then(|(old, index)| old.students.get(index))
+            .map(StudentRecord::key);
+        let session = previous
+            .as_ref()
+            .zip(self.selected_session)
+            .and_then(|(old, index)| {
+                let date = old.session_dates.get(index)?;
+                Some((*date, old.session_sources.get(index).cloned()))
+            });
+        self.selected_student = report
+            .as_ref()
+            .zip(student)
+            .and_then(|(new, key)| new.students.iter().position(|record| record.key() == key));
+        self.selected_session = report
+            .as_ref()
+            .zip(session)
+            .and_then(|(new, (date, source))| {
+                (0..new.session_dates.len()).find(|&index| {
+                    new.session_dates[index] == date
+                        && new.session_sources.get(index) == source.as_ref()
+                })
+            });
+        self.override_draft = None;
+        self.report = report;
+    }
+
+    /// Opens the override editor for one session of a student of the report.
+    pub fn edit_override(&mut self, student: usize, session: usize) {
+        let Some(report) = &self.report else {
+            return;
+        };
+        let Some(record) = report.students.get(student) else {
+            return;
+        };
+        let Some(attendance) = record.history.get(session) else {
+            return;
+        };
+        let source = report.session_sources.get(session).cloned();
+        let reason = overrides::find_override(
+            &self.overrides,
+            &record.key(),
+            attendance.date,
+            source.as_deref(),
+        )
+        .map(|existing| existing.reason.clone())
+        .unwrap_or_default();
+        self.override_draft = Some(OverrideDraft {
+            student,
+            session,
+            status: attendance.status,
+            reason,
+        });
+    }
+
+    /// Records the edited override and applies it to the report.
+    pub fn save_override(&mut self) -> Result<(), PresenceError> {
+        let (Some(draft), Some(report)) = (&self.override_draft, &mut self.report) else {
+            return Ok(());
+        };
+        let Some(record) = report.students.get(draft.student) else {
+            return Ok(());
+        };
+        let Some(attendance) = record.history.get(draft.session) else {
+            return Ok(());
+        };
+        let entry = StatusOverride::new(
+            record.key(),
+            attendance.date,
+            report.session_sources.get(draft.session).cloned(),
+            draft.status,
+            &draft.reason,
+        )?;
+        overrides::set_override(&mut self.overrides, entry);
+        overrides::apply_overrides(report, &self.overrides);
+        self.override_draft = None;
+        Ok(())
+    }
+
+    /// Withdraws the override of the session being edited, restoring the
+    /// computed status. The withdrawal is kept in the audit log.
+    pub fn remove_override(&mut self) {
+        let (Some(draft), Some(report)) = (self.override_draft.take(), &mut self.report) else {
+            return;
+        };
+        let Some(record) = report.students.get(draft.student) else {
+            return;
+        };
+        if let Some(attendance) = record.history.get(draft.session) {
+            let source = report.session_sources.get(draft.session).cloned();
+            let key = record.key();
+            // The editor starts with the reason of the current override; only
+            // a newly typed one explains the removal.
+            let reason =
+                overrides::find_override(&self.overrides, &key, attendance.date, source.as_deref())
+                    .filter(|current| current.reason != draft.reason.trim())
+                    .map_or("", |_| draft.reason.as_str());
+            overrides::remove_override(
+                &mut self.overrides,
+                &key,
+                attendance.date,
+                source.as_deref(),
+                reason,
+            );
+            overrides::apply_overrides(report, &self.overrides);
+        }
+    }
+
+    /// Indices into `report.students` of the students matching the search
+    /// and filter, in display order.
+    pub fn visible_students(&self) -> Vec<usize> {
+        let Some(report) = &self.report else {
+            return Vec::new();
+        };
+        let query = self.student_query.trim().to_lowercase();
+        let mut visible: Vec<usize> = report
+            .students
+            .iter()
+            .enumerate()
+            .filter(|(_, student)| {
+                query.is_empty()
+                    || [&student.name, &student.surname, &student.id, &student.email]
+                        .iter()
+                        .any(|field| field.to_lowercase().contains(&query))
+            })
+            .filter(|(_, student)| match self.student_filter {
+                None => true,
+                Some(StudentFilter::AtRisk) => report.is_at_risk(student),
+                Some(StudentFilter::Ineligible) => !report.is_eligible(student),
+                Some(StudentFilter::Overrides) => student.has_overrides(),
+                Some(StudentFilter::Guests) => student.is_guest(),
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        // Students arrive sorted by name, so a stable sort keeps ties in name order.
+        let students = &report.students;
+        match self.student_sort {
+            StudentSort::Name => {}
+            StudentSort::Id => visible.sort_by(|&a, &b| students[a].id.cmp(&students[b].id)),
+            StudentSort::Score => {
+                visible.sort_by(|&a, &b| students[a].score.total_cmp(&students[b].score))
+            }
+            StudentSort::Absences => visible.sort_by_key(|&index| students[index].absent),
+            StudentSort::Lateness => visible.sort_by_key(|&index| students[index].late),
+            StudentSort::Rate => visible.sort_by(|&a, &b| {
+                students[a]
+                    .attendance_rate()
+                    .total_cmp(&students[b].attendance_rate())
+            }),
+        }
+        if self.sort_descending {
+            visible.reverse();
+        }
+        visible
+    }
+
+    /// Moves the selection `step` rows through the visible students, e.g. for
+    /// arrow keys, and returns its new position in that list.
+    pub fn move_selection(&mut self, step: isize) -> Option<usize> {
+        let visible = self.visible_students();
+        let last = visible.len().checked_sub(1)?;
+        let position = match self
+            .selected_student
+            .and_then(|selected| visible.iter().position(|&index| index == selected))
+        {
+            Some(position) => position.saturating_add_signed(step).min(last),
+            None if step < 0 => last,
+            None => 0,
+        };
+        self.selected_student = Some(visible[position]);
+        self.override_draft = None;
+        Some(position)
+    }
+
+    /// Follows the mouse while a table column is resized. The first position
+    /// after the drag started becomes its origin.
+    pub fn drag_column(&mut self, x: f32) {
+        let Some(drag) = &mut self.column_drag else {
+            return;
+        };
+        match drag.origin {
+            None => drag.origin = Some(x),
+            Some(origin) => {
+                if let Some(width) = self.column_widths.get_mut(drag.column) {
+                    *width = (drag.width + x - origin).max(table::MIN_COLUMN_WIDTH);
+                }
+            }
+        }
+    }
+
+    /// Adds a folder or file to the sources, enabled. Returns false when it
+    /// is already one of them.
+    pub fn add_source(&mut self, path: PathBuf) -> bool {
+        if self.sources.iter().any(|source| source.path == path) {
+            return false;
+        }
+        self.sources.push(SourceEntry {
+            path,
+            enabled: true,
+        });
+        self.discovered = None;
+        true
+    }
+
+    /// Counts a dropped file that added `added` sources and returns the batch
+    /// number to hand back to [`Self::finish_drop`].
+    pub fn collect_drop(&mut self, added: usize) -> u64 {
+        self.drop_batch += 1;
+        self.dropped_sources += added;
+        self.drop_batch
+    }
+
+    /// Whether `batch` was the last file of its drop and the drop should be
+    /// analyzed. Files dropped later make the earlier batches stale.
+    pub fn finish_drop(&mut self, batch: u64) -> bool {
+        if batch != self.drop_batch {
+            return false;
+        }
+        std::mem::take(&mut self.dropped_sources) > 0 && self.analyze_on_drop
+    }
+
+    pub fn remove_source(&mut self, index: usize) {
+        if index < self.sources.len() {
+            self.sources.remove(index);
+            self.discovered = None;
+        }
+    }
+
+    pub fn set_source_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(source) = self.sources.get_mut(index) {
+            source.enabled = enabled;
+            self.discovered = None;
+        }
+    }
+
+    /// The sources that are analyzed and watched, in list order.
+    pub fn enabled_sources(&self) -> Vec<PathBuf> {
+        self.sources
+            .iter()
+            .filter(|source| source.enabled)
+            .map(|source| source.path.clone())
+            .collect()
+    }
+
+    /// Name of the open project, if any.
+    pub fn project_name(&self) -> Option<String> {
+        self.project_path.as_deref().map(project::project_name)
+    }
+
+    /// Name shown for the active profile.
+    pub fn profile_name(&self) -> String {
+        self.profile_path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "unsaved".to_string())
+    }
+
+    /// Re-checks the analysis settings, keeping one message per field.
+    pub fn validate_config(&mut self) {
+        self.config_errors.clear();
+        for error in validate_config(&self.to_config()) {
+            if let PresenceError::Config { field, .. } = &error
+                && self.config_error(field).is_none()
+            {
+                self.config_errors.push((*field, error.to_string()));
+            }
+        }
+    }
+
+    /// Why a settings field is invalid, if it is.
+    pub fn config_error(&self, field: &str) -> Option<&str> {
+        self.config_errors
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, message)| message.as_str())
+    }
+
+    /// The ticked export columns, in order, with their custom labels.
+    pub fn selected_columns(&self) -> Vec<ColumnSpec> {
+        self.export_columns
+            .iter()
+            .filter(|choice| choice.enabled)
+            .map(|choice| ColumnSpec {
+                column: choice.column,
+                label: Some(choice.label.trim().to_string()).filter(|label| !label.is_empty()),
+            })
+            .collect()
+    }
+
+    /// Moves an export column one place up (`-1`) or down (`1`).
+    pub fn move_column(&mut self, index: usize, step: isize) {
+        if let Some(target) = index.checked_add_signed(step)
+            && index < self.export_columns.len()
+            && target < self.export_columns.len()
+        {
+            self.export_columns.swap(index, target);
         }
     }
 
@@ -52,206 +587,259 @@ impl AppState {
             absent_minutes: self.absent_minutes.clone(),
             total_points: self.total_points.clone(),
             late_penalty: self.late_penalty.clone(),
+            min_attendance: self.min_attendance.clone(),
         }
     }
+
+    pub fn to_export_options(&self) -> Result<ExportOptions, PresenceError> {
+        Ok(ExportOptions {
+            course_name: self.course_name.clone(),
+            term: self.term.clone(),
+            student_pages: self.student_pages,
+            // Round trip through the specification so an empty selection is rejected
+            // the same way as in profiles.
+            columns: columns::parse_columns(&columns::format_columns(&self.selected_columns()))?,
+            grade_item: if self.grade_item.trim().is_empty() {
+                "Attendance".to_string()
+            } else {
+                self.grade_item.trim().to_string()
+            },
+        })
+    }
 }
 
-pub fn load_attendance(
-    directory: PathBuf,
-    config: AttendanceConfig,
-) -> Result<AttendanceReport, String> {
-    let mut files: Vec<PathBuf> = if directory.is_file() {
-        if is_attendance_file(&directory) {
-            vec![directory]
-        } else {
-            return Err("Selected file is not a supported CSV/XLSX attendance export.".to_string());
-        }
-    } else if directory.is_dir() {
-        std::fs::read_dir(&directory)
-            .map_err(|error| format!("Failed to read directory: {error}"))?
-            .filter_map(|entry| entry.ok())
-            .map(|entry| entry.path())
-            .filter(|path| is_attendance_file(path))
-            .collect()
-    } else {
-        return Err("Please select a valid directory or attendance file.".to_string());
-    };
-    files.sort();
+/// An analysis running in the background. Each one gets a fresh id, so
+/// starting another replaces the subscription that runs it.
+#[derive(Debug, Clone)]
+pub struct LoadJob {
+    pub id: u64,
+    pub files: Vec<PathBuf>,
+    pub config: AttendanceConfig,
+    pub cancel: CancelToken,
+}
 
-    if files.is_empty() {
-        return Err("No attendance CSV/XLSX files found in the directory.".to_string());
-    }
+/// A table column being resized: its index, the cursor position the drag
+/// started at and the column's width at that moment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnDrag {
+    pub column: usize,
+    pub origin: Option<f32>,
+    pub width: f32,
+}
 
-    let mut sessions: Vec<Vec<Participant>> = Vec::new();
+/// Column the student list is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StudentSort {
+    Name,
+    Id,
+    Score,
+    Absences,
+    Lateness,
+    Rate,
+}
 
-    for path in files {
-        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        let data = std::fs::read(&path)
-            .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
-        let participants = core::parse_participants(&data, extension)?;
-        if !participants.is_empty() {
-            sessions.push(participants);
-        }
+impl StudentSort {
+    pub const ALL: [StudentSort; 6] = [
+        StudentSort::Name,
+        StudentSort::Id,
+        StudentSort::Score,
+        StudentSort::Absences,
+        StudentSort::Lateness,
+        StudentSort::Rate,
+    ];
+}
+
+impl fmt::Display for StudentSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            StudentSort::Name => "Name",
+            StudentSort::Id => "ID",
+            StudentSort::Score => "Score",
+            StudentSort::Absences => "Absences",
+            StudentSort::Lateness => "Lateness",
+            StudentSort::Rate => "Attendance %",
+        };
+        write!(f, "{label}")
     }
+}
 
-    core::generate_report(sessions, config)
+/// Top-level views below the configuration panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Students,
+    Dashboard,
+    Sessions,
 }
 
-pub fn save_report(report: AttendanceReport, format: ReportFormat) -> Result<PathBuf, String> {
-    let file = rfd::FileDialog::new()
-        .save_file()
-        .ok_or_else(|| "Save cancelled. Please choose a file path to export.".to_string())?;
-    match format {
-        ReportFormat::Csv => write_csv(&file, &report)?,
-        ReportFormat::Txt => write_text(&file, &report)?,
-        ReportFormat::Pdf => write_pdf(&file, &report)?,
+impl Tab {
+    pub const ALL: [Tab; 3] = [Tab::Students, Tab::Dashboard, Tab::Sessions];
+}
+
+impl fmt::Display for Tab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Tab::Students => "STUDENTS",
+            Tab::Dashboard => "DASHBOARD",
+            Tab::Sessions => "SESSIONS",
+        };
+        write!(f, "{label}")
     }
-    Ok(file)
 }
 
-fn is_attendance_file(path: &Path) -> bool {
-    matches!(
-        path.extension().and_then(|ext| ext.to_str()),
-        Some("csv") | Some("xlsx") | Some("xls")
-    )
+/// Quick filters of the student list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StudentFilter {
+    AtRisk,
+    Ineligible,
+    Overrides,
+    Guests,
 }
 
-fn write_csv(path: &Path, report: &AttendanceReport) -> Result<(), String> {
-    let mut writer =
-        csv::Writer::from_path(path).map_err(|error| format!("Failed to create CSV: {error}"))?;
-    writer
-        .write_record(["Name", "Surname", "ID", "Normal", "Late", "Absent", "Score"])
-        .map_err(|error| format!("Failed to write CSV header: {error}"))?;
-    for student in &report.students {
-        writer
-            .write_record([
-                &student.name,
-                &student.surname,
-                &student.id,
-                &student.normal.to_string(),
-                &student.late.to_string(),
-                &student.absent.to_string(),
-                &format!("{:.1}/{:.1}", student.score, report.total_points),
-            ])
-            .map_err(|error| format!("Failed to write CSV row: {error}"))?;
-    }
-    writer
-        .flush()
-        .map_err(|error| format!("Failed to finalize CSV: {error}"))?;
-    Ok(())
+impl StudentFilter {
+    pub const ALL: [StudentFilter; 4] = [
+        StudentFilter::AtRisk,
+        StudentFilter::Ineligible,
+        StudentFilter::Overrides,
+        StudentFilter::Guests,
+    ];
 }
 
-fn write_text(path: &Path, report: &AttendanceReport) -> Result<(), String> {
-    let mut file = File::create(path).map_err(|error| format!("Failed to create text: {error}"))?;
-    writeln!(file, "Name\tSurname\tID\tNormal\tLate\tAbsent\tScore")
-        .map_err(|error| format!("Failed to write text header: {error}"))?;
-    for student in &report.students {
-        writeln!(
-            file,
-            "{}\t{}\t{}\t{}\t{}\t{}\t{:.1}/{:.1}",
-            student.name,
-            student.surname,
-            student.id,
-            student.normal,
-            student.late,
-            student.absent,
-            student.score,
-            report.total_points
-        )
-        .map_err(|error| format!("Failed to write text row: {error}"))?;
+impl fmt::Display for StudentFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            StudentFilter::AtRisk => "AT RISK",
+            StudentFilter::Ineligible => "INELIGIBLE",
+            StudentFilter::Overrides => "OVERRIDES",
+            StudentFilter::Guests => "GUESTS",
+        };
+        write!(f, "{label}")
     }
-    Ok(())
 }
 
-fn write_pdf(path: &Path, report: &AttendanceReport) -> Result<(), String> {
-    let final_path = if let Some(extension) = path.extension() {
-        if extension != "pdf" {
-            path.with_extension("pdf")
-        } else {
-            path.to_path_buf()
+/// A folder, export or archive of the source list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceEntry {
+    pub path: PathBuf,
+    /// Analyzed and watched; disabled sources stay in the list and project.
+    pub enabled: bool,
+}
+
+/// One row of the export column editor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnChoice {
+    pub column: ExportColumn,
+    pub enabled: bool,
+    /// Custom header; empty for the column's default label.
+    pub label: String,
+}
+
+impl ColumnChoice {
+    /// Lists the columns of a specification first, in its order, followed by
+    /// the remaining columns unticked.
+    pub fn from_specs(specs: &[ColumnSpec]) -> Vec<ColumnChoice> {
+        let mut choices: Vec<ColumnChoice> = Vec::new();
+        for spec in specs {
+            if choices.iter().all(|choice| choice.column != spec.column) {
+                choices.push(ColumnChoice {
+                    column: spec.column,
+                    enabled: true,
+                    label: spec.label.clone().unwrap_or_default(),
+                });
+            }
         }
-    } else {
-        path.with_extension("pdf")
-    };
+        for column in ExportColumn::ALL {
+            if choices.iter().all(|choice| choice.column != column) {
+                choices.push(ColumnChoice {
+                    column,
+                    enabled: false,
+                    label: String::new(),
+                });
+            }
+        }
+        choices
+    }
+}
 
-    let font_data = include_bytes!("../assets/fonts/DejaVuSans.ttf");
-    let bold_data = include_bytes!("../assets/fonts/DejaVuSans-Bold.ttf");
+/// Status override being edited, by student and session index in the report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverrideDraft {
+    pub student: usize,
+    pub session: usize,
+    pub status: AttendanceStatus,
+    pub reason: String,
+}
 
-    let font = genpdf::fonts::FontData::new(font_data.to_vec(), None)
-        .map_err(|e| format!("Failed to load font: {}", e))?;
-    let bold_font = genpdf::fonts::FontData::new(bold_data.to_vec(), None)
-        .map_err(|e| format!("Failed to load bold font: {}", e))?;
+/// Entry of the recent projects list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentProject(pub PathBuf);
 
-    let font_family = genpdf::fonts::FontFamily {
-        regular: font.clone(),
-        bold: bold_font.clone(),
-        italic: font,
-        bold_italic: bold_font,
-    };
-    let mut doc = genpdf::Document::new(font_family);
-    doc.set_title("Attendance Report");
-
-    let blue = Color::Rgb(38, 139, 210);
-    let violet = Color::Rgb(108, 113, 196);
-    let base01 = Color::Rgb(88, 110, 117);
-    let base00 = Color::Rgb(101, 123, 131);
-
-    let mut title = genpdf::elements::Paragraph::new("Attendance Report");
-    title.set_alignment(genpdf::Alignment::Center);
-    doc.push(title.styled(Style::new().with_color(violet).with_font_size(20).bold()));
-    doc.push(genpdf::elements::Break::new(1.0));
-
-    let mut table = genpdf::elements::TableLayout::new(vec![3, 3, 2, 2, 2, 2, 2]);
-    table.set_cell_decorator(genpdf::elements::FrameCellDecorator::new(true, true, true));
-
-    let header_style = Style::new().with_color(blue).bold();
-
-    table
-        .row()
-        .element(padded_text("Name", header_style))
-        .element(padded_text("Surname", header_style))
-        .element(padded_text("ID", header_style))
-        .element(padded_text("Normal", header_style))
-        .element(padded_text("Late", header_style))
-        .element(padded_text("Absent", header_style))
-        .element(padded_text("Score", header_style))
-        .push()
-        .map_err(|error| format!("Failed to write PDF header: {error}"))?;
-    for (i, student) in report.students.iter().enumerate() {
-        let color = if i % 2 == 0 { base01 } else { base00 };
-        let row_style = Style::new().with_color(color);
-        table
-            .row()
-            .element(padded_text(student.name.clone(), row_style))
-            .element(padded_text(student.surname.clone(), row_style))
-            .element(padded_text(student.id.clone(), row_style))
-            .element(padded_text(student.normal.to_string(), row_style))
-            .element(padded_text(student.late.to_string(), row_style))
-            .element(padded_text(student.absent.to_string(), row_style))
-            .element(padded_text(
-                format!("{:.1}/{:.1}", student.score, report.total_points),
-                row_style,
-            ))
-            .push()
-            .map_err(|error| format!("Failed to write PDF row: {error}"))?;
-    }
-    doc.push(table);
-    doc.render_to_file(&final_path)
-        .map_err(|error| format!("Failed to write PDF: {error}"))?;
-    Ok(())
+impl fmt::Display for RecentProject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let folder = self
+            .0
+            .parent()
+            .map(|folder| folder.display().to_string())
+            .unwrap_or_default();
+        write!(f, "{} ({folder})", project::project_name(&self.0))
+    }
 }
 
-fn padded_text(text: impl Into<String>, style: Style) -> impl Element {
-    PaddedElement::new(
-        genpdf::elements::Paragraph::new(text.into()).styled(style),
-        genpdf::Margins::trbl(2.0, 2.0, 2.0, 2.0),
-    )
+/// Asks for a destination with the native save dialog and exports the report there.
+pub fn save_report(
+    report: AttendanceReport,
+    format: ReportFormat,
+    options: ExportOptions,
+) -> Result<PathBuf, PresenceError> {
+    let file = rfd::FileDialog::new()
+        .set_file_name(format.file_name(&options.file_stem()))
+        .save_file()
+        .ok_or(PresenceError::Cancelled)?;
+    export::export_report_to_path(&report, format, &options, &file)
+}
+
+/// Asks for a folder with the native dialog and exports every format into it.
+pub fn save_all_reports(
+    report: AttendanceReport,
+    options: ExportOptions,
+) -> Result<Vec<PathBuf>, PresenceError> {
+    let folder = rfd::FileDialog::new()
+        .pick_folder()
+        .ok_or(PresenceError::Cancelled)?;
+    export::export_formats(&report, &ReportFormat::ALL, &options, &folder)
+}
+
+/// Turns an error into the sentence shown in the status bar: the error itself
+/// followed by its causes, plus a hint where the fix is not obvious.
+pub fn user_message(error: &PresenceError) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message = format!("{}: {cause}", message.trim_end_matches('.'));
+        source = cause.source();
+    }
+    let hint = match error {
+        PresenceError::Cancelled => "Nothing was saved.",
+        PresenceError::Csv { .. }
+        | PresenceError::Excel { .. }
+        | PresenceError::EmptyWorkbook { .. }
+        | PresenceError::InvalidValue { .. } => {
+            "Make sure the file is an unmodified attendance export."
+        }
+        PresenceError::Archive { .. } | PresenceError::ArchiveEntryTooLarge { .. } => {
+            "Re-download the archive or extract it and select the folder."
+        }
+        _ => "",
+    };
+    match hint {
+        "" => message,
+        hint if message.ends_with('.') => format!("{message} {hint}"),
+        hint => format!("{message}. {hint}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::StudentRecord;
 
     #[test]
     fn test_app_state_defaults() {
@@ -269,130 +857,314 @@ mod tests {
     }
 
     #[test]
-    fn test_load_attendance() {
+    fn test_column_editor() {
+        let mut state = AppState::new();
+        assert_eq!(state.export_columns.len(), ExportColumn::ALL.len());
+        assert_eq!(
+            state.to_profile().unwrap().columns,
+            columns::DEFAULT_COLUMNS
+        );
+
+        // Untick surname, move id to the top and rename it
+        state.export_columns[1].enabled = false;
+        state.move_column(2, -1);
+        state.move_column(1, -1);
+        state.move_column(0, -1);
+        state.export_columns[0].label = " Student ID ".to_string();
+        let profile = state.to_profile().unwrap();
+        assert_eq!(
+            profile.columns,
+            "id:Student ID,name,normal,late,absent,score"
+        );
+
+        let mut restored = AppState::new();
+        restored.apply_profile(&profile);
+        assert_eq!(restored.selected_columns(), state.selected_columns());
+
+        for choice in &mut state.export_columns {
+            choice.enabled = false;
+        }
+        assert!(state.to_export_options().is_err());
+    }
+
+    #[test]
+    fn test_profile_round_trip() {
+        let mut state = AppState::new();
+        state.class_start = " 9:05".to_string();
+        state.late_penalty = "0.25".to_string();
+        state.min_attendance = "75".to_string();
+        state.course_name = "CS101".to_string();
+        state.recursive = true;
+        state.exclude = "*_summary.csv, ,week-00/*".to_string();
+        let profile = state.to_profile().unwrap();
+        assert_eq!(profile.exclude, vec!["*_summary.csv", "week-00/*"]);
+        assert_eq!(profile.class_start, "09:05");
+        assert_eq!(profile.late_penalty, 0.25);
+        assert_eq!(profile.min_attendance, 75.0);
+
+        let mut restored = AppState::new();
+        restored.apply_profile(&profile);
+        assert_eq!(restored.class_start, "09:05");
+        assert_eq!(restored.course_name, "CS101");
+        assert_eq!(restored.exclude, "*_summary.csv, week-00/*");
+        assert_eq!(restored.to_profile().unwrap(), profile);
+
+        state.late_minutes = "soon".to_string();
+        assert!(state.to_profile().is_err());
+    }
+
+    #[test]
+    fn test_config_errors_follow_edits() {
+        let mut state = AppState::new();
+        assert!(state.config_errors.is_empty());
+
+        state.late_minutes = "40".to_string();
+        state.total_points = "-1".to_string();
+        state.validate_config();
+        assert!(state.config_error("absent_minutes").is_some());
+        assert!(state.config_error("total_points").is_some());
+        assert_eq!(state.config_error("late_minutes"), None);
+
+        state.absent_minutes = "45".to_string();
+        state.total_points = "10".to_string();
+        state.validate_config();
+        assert!(state.config_errors.is_empty());
+    }
+
+    #[test]
+    fn test_drop_is_analyzed_once() {
+        let mut state = AppState::new();
+        state.analyze_on_drop = true;
+
+        // Three files of one drop, the last already a source
+        let batches = [1, 1, 0].map(|added| state.collect_drop(added));
+        let finished: Vec<bool> = batches
+            .iter()
+            .map(|batch| state.finish_drop(*batch))
+            .collect();
+        assert_eq!(finished, [false, false, true]);
+
+        // A drop of known sources only is not analyzed again
+        let batch = state.collect_drop(0);
+        assert!(!state.finish_drop(batch));
+
+        state.analyze_on_drop = false;
+        let batch = state.collect_drop(1);
+        assert!(!state.finish_drop(batch));
+        assert_eq!(state.dropped_sources, 0);
+    }
+
+    #[test]
+    fn test_project_round_trip() {
+        let mut state = AppState::new();
+        assert!(state.add_source(PathBuf::from("/data/cs101")));
+        assert!(state.add_source(PathBuf::from("/data/in-person.csv")));
+        assert!(!state.add_source(PathBuf::from("/data/cs101")));
+        state.set_source_enabled(1, false);
+        assert_eq!(state.enabled_sources(), vec![PathBuf::from("/data/cs101")]);
+        state.notes = "Week 3 was cancelled.".to_string();
+        state.term = "Fall 2023".to_string();
+        let project = state.to_project().unwrap();
+        assert_eq!(
+            project.sources,
+            vec![
+                PathBuf::from("/data/cs101"),
+                PathBuf::from("/data/in-person.csv")
+            ]
+        );
+        assert_eq!(
+            project.disabled_sources,
+            vec![PathBuf::from("/data/in-person.csv")]
+        );
+
+        let mut restored = AppState::new();
+        restored.apply_project(project);
+        assert_eq!(restored.sources, state.sources);
+        assert_eq!(restored.notes, "Week 3 was cancelled.");
+        assert_eq!(restored.term, "Fall 2023");
+    }
+
+    #[test]
+    fn test_visible_students_search_sort_and_filter() {
         let dir = tempfile::tempdir().unwrap();
-        let file_path = dir.path().join("session1.csv");
-        let mut file = File::create(&file_path).unwrap();
-        // Quote the date field as per CSV requirement for commas
-        writeln!(
-            file,
-            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:30:00 PM\""
+        std::fs::write(
+            dir.path().join("session1.csv"),
+            "Name,Email,First Join\n\
+             Ann Able,ann@example.com,\"10/25/23, 1:30:00 PM\"\n\
+             Bob Baker,bob@example.com,\"10/25/23, 1:45:00 PM\"\n\
+             Guest User,,\"10/25/23, 2:30:00 PM\"",
         )
         .unwrap();
-
-        let config = AttendanceConfig {
-            class_start: "13:30".to_string(),
-            class_end: "15:00".to_string(),
-            late_minutes: "10".to_string(),
-            absent_minutes: "30".to_string(),
-            total_points: "10.0".to_string(),
-            late_penalty: "0.5".to_string(),
+        let mut state = AppState::new();
+        state.report =
+            Some(presence_core::load_attendance(dir.path().into(), state.to_config()).unwrap());
+        let names = |state: &AppState| -> Vec<String> {
+            let report = state.report.as_ref().unwrap();
+            state
+                .visible_students()
+                .into_iter()
+                .map(|index| report.students[index].name.clone())
+                .collect()
         };
+        assert_eq!(names(&state), ["Ann", "Bob", "Guest"]);
 
-        // Test loading from directory
-        let report = load_attendance(dir.path().to_path_buf(), config.clone())
-            .expect("Failed to load from dir");
-        assert_eq!(report.sessions, 1);
-        assert_eq!(report.students.len(), 1);
+        state.student_query = "BOB@".to_string();
+        assert_eq!(names(&state), ["Bob"]);
 
-        // Test loading from single file
-        let report_file = load_attendance(file_path, config).expect("Failed to load from file");
-        assert_eq!(report_file.sessions, 1);
-        assert_eq!(report_file.students.len(), 1);
+        state.student_query.clear();
+        state.student_sort = StudentSort::Score;
+        state.sort_descending = true;
+        assert_eq!(names(&state), ["Ann", "Bob", "Guest"]);
+        state.student_sort = StudentSort::Lateness;
+        assert_eq!(names(&state)[0], "Bob");
+
+        state.student_filter = Some(StudentFilter::Guests);
+        assert_eq!(names(&state), ["Guest"]);
+        state.student_filter = Some(StudentFilter::Ineligible);
+        assert_eq!(names(&state), ["Guest"]);
     }
 
     #[test]
-    fn test_write_csv() {
+    fn test_selection_follows_report_changes() {
         let dir = tempfile::tempdir().unwrap();
-        let file_path = dir.path().join("output.csv");
-
-        let report = AttendanceReport {
-            students: vec![StudentRecord {
-                name: "John".to_string(),
-                surname: "Doe".to_string(),
-                id: "john".to_string(),
-                email: "john@example.com".to_string(),
-                normal: 1,
-                late: 0,
-                absent: 0,
-                score: 1.0,
-            }],
-            sessions: 1,
-            total_points: 10.0,
+        let write = |name: &str, date: &str, names: &[&str]| {
+            let rows: Vec<String> = names
+                .iter()
+                .map(|name| {
+                    let email = name.split(' ').next().unwrap().to_lowercase();
+                    format!("{name},{email}@example.com,\"{date}, 1:30:00 PM\"")
+                })
+                .collect();
+            let contents = format!("Name,Email,First Join\n{}", rows.join("\n"));
+            std::fs::write(dir.path().join(name), contents).unwrap();
+        };
+        let load = |state: &AppState| {
+            presence_core::load_attendance(dir.path().into(), state.to_config()).unwrap()
         };
+        write("session2.csv", "10/27/23", &["Ann Able", "Bob Baker"]);
+        let mut state = AppState::new();
+        state.set_report(Some(load(&state)));
+        let report = state.report.as_ref().unwrap();
+        let bob = report
+            .students
+            .iter()
+            .position(|student| student.email == "bob@example.com")
+            .unwrap();
+        state.selected_student = Some(bob);
+        state.selected_session = Some(0);
 
-        write_csv(&file_path, &report).expect("Failed to write CSV");
+        // An earlier session and a new student shift both indices
+        write("session1.csv", "10/20/23", &["Aaron Aardvark", "Bob Baker"]);
+        state.set_report(Some(load(&state)));
+        let report = state.report.as_ref().unwrap();
+        assert_eq!(report.students.len(), 3);
+        let selected = &report.students[state.selected_student.unwrap()];
+        assert_eq!(selected.email, "bob@example.com");
+        let session = state.selected_session.unwrap();
+        assert_eq!(
+            report.session_sources[session],
+            dir.path().join("session2.csv")
+        );
 
-        let content = std::fs::read_to_string(&file_path).unwrap();
-        assert!(content.contains("Name,Surname,ID"));
-        assert!(content.contains("John,Doe,john,1,0,0,1.0/10.0"));
+        // Gone from the new report
+        std::fs::remove_file(dir.path().join("session2.csv")).unwrap();
+        write("session1.csv", "10/20/23", &["Ann Able"]);
+        state.set_report(Some(load(&state)));
+        assert_eq!(state.selected_student, None);
+        assert_eq!(state.selected_session, None);
     }
 
     #[test]
-    fn test_write_text() {
+    fn test_move_selection_and_drag_column() {
         let dir = tempfile::tempdir().unwrap();
-        let file_path = dir.path().join("output.txt");
-
-        let report = AttendanceReport {
-            students: vec![StudentRecord {
-                name: "John".to_string(),
-                surname: "Doe".to_string(),
-                id: "john".to_string(),
-                email: "john@example.com".to_string(),
-                normal: 1,
-                late: 0,
-                absent: 0,
-                score: 1.0,
-            }],
-            sessions: 1,
-            total_points: 10.0,
-        };
+        std::fs::write(
+            dir.path().join("session1.csv"),
+            "Name,Email,First Join\n\
+             Ann Able,ann@example.com,\"10/25/23, 1:30:00 PM\"\n\
+             Bob Baker,bob@example.com,\"10/25/23, 1:45:00 PM\"",
+        )
+        .unwrap();
+        let mut state = AppState::new();
+        state.report =
+            Some(presence_core::load_attendance(dir.path().into(), state.to_config()).unwrap());
 
-        write_text(&file_path, &report).expect("Failed to write TXT");
+        assert_eq!(state.move_selection(1), Some(0));
+        assert_eq!(state.move_selection(10), Some(1));
+        state.sort_descending = true;
+        // The selection follows the student, not the row
+        assert_eq!(state.move_selection(0), Some(0));
+        assert_eq!(state.selected_student, Some(1));
 
-        let content = std::fs::read_to_string(&file_path).unwrap();
-        assert!(content.contains("Name\tSurname\tID"));
-        assert!(content.contains("John\tDoe\tjohn\t1\t0\t0\t1.0/10.0"));
+        state.column_drag = Some(ColumnDrag {
+            column: 0,
+            origin: None,
+            width: 150.0,
+        });
+        state.drag_column(500.0);
+        state.drag_column(530.0);
+        assert_eq!(state.column_widths[0], 180.0);
+        state.drag_column(0.0);
+        assert_eq!(state.column_widths[0], table::MIN_COLUMN_WIDTH);
     }
 
     #[test]
-    fn test_load_attendance_errors() {
-        let config = AttendanceConfig {
-            class_start: "13:30".to_string(),
-            class_end: "15:00".to_string(),
-            late_minutes: "10".to_string(),
-            absent_minutes: "30".to_string(),
-            total_points: "10.0".to_string(),
-            late_penalty: "0.5".to_string(),
-        };
+    fn test_save_and_remove_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("session1.csv");
+        std::fs::write(
+            &file_path,
+            "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 2:30:00 PM\"",
+        )
+        .unwrap();
+        let mut state = AppState::new();
+        state.report = Some(presence_core::load_attendance(file_path, state.to_config()).unwrap());
 
-        // Test non-existent directory
-        let result = load_attendance(PathBuf::from("non_existent_dir_12345"), config.clone());
-        assert!(result.is_err());
-        assert!(
-            result
-                .unwrap_err()
-                .contains("Please select a valid directory or attendance file.")
+        state.edit_override(0, 0);
+        let draft = state.override_draft.as_mut().unwrap();
+        assert_eq!(draft.status, AttendanceStatus::Absent);
+        draft.status = AttendanceStatus::Normal;
+        assert!(state.save_override().is_err());
+
+        state.override_draft.as_mut().unwrap().reason = "Was in the room".to_string();
+        state.save_override().unwrap();
+        assert_eq!(state.overrides.len(), 1);
+        assert_eq!(state.report.as_ref().unwrap().students[0].normal, 1);
+        assert_eq!(state.to_project().unwrap().overrides, state.overrides);
+
+        state.edit_override(0, 0);
+        assert_eq!(
+            state.override_draft.as_ref().unwrap().reason,
+            "Was in the room"
         );
+        state.remove_override();
+        assert_eq!(state.overrides.len(), 2);
+        assert_eq!(state.overrides[1].status, None);
+        assert_eq!(state.overrides[1].reason, "Override removed");
+        assert_eq!(state.report.as_ref().unwrap().students[0].absent, 1);
+    }
 
-        // Test directory with no valid files
-        let dir = tempfile::tempdir().unwrap();
-        let file_path = dir.path().join("readme.txt");
-        File::create(&file_path).unwrap();
-
-        let result = load_attendance(dir.path().to_path_buf(), config.clone());
-        assert!(result.is_err());
-        assert!(
-            result
-                .unwrap_err()
-                .contains("No attendance CSV/XLSX files found")
+    #[test]
+    fn test_user_message() {
+        assert_eq!(
+            user_message(&PresenceError::Cancelled),
+            "Cancelled. Nothing was saved."
         );
 
-        // Test invalid file type selected directly
-        let file_path = dir.path().join("invalid.txt");
-        File::create(&file_path).unwrap();
-        let result = load_attendance(file_path, config.clone());
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not a supported CSV/XLSX"));
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("session1.csv");
+        std::fs::write(
+            &file_path,
+            "Name,Email,First Join\nJohn Doe,john@example.com,soon",
+        )
+        .unwrap();
+        let error = presence_core::load_attendance(file_path.clone(), AppState::new().to_config())
+            .unwrap_err();
+        assert_eq!(
+            user_message(&error),
+            format!(
+                "Invalid First Join 'soon' in {} at line 2. Make sure the file is an unmodified attendance export.",
+                file_path.display()
+            )
+        );
     }
 }
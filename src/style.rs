@@ -1,6 +1,8 @@
 use iced::overlay::menu;
-use iced::widget::{button, container, pick_list, scrollable, text_input};
+use iced::widget::{button, checkbox, container, pick_list, progress_bar, scrollable, text_input};
 use iced::{Background, Border, Color, Shadow, Theme, Vector};
+use presence_core::AttendanceStatus;
+use presence_core::charts::Tone;
 
 // Aggressive Solarized Dark Palette
 // Darker backgrounds for higher contrast
@@ -19,7 +21,6 @@ pub const YELLOW: Color = Color::from_rgb(0.94, 0.78, 0.0); // #f0c600
 #[allow(dead_code)]
 pub const ORANGE: Color = Color::from_rgb(1.0, 0.37, 0.0); // #ff5f00
 pub const RED: Color = Color::from_rgb(1.0, 0.2, 0.2); // #ff3333
-#[allow(dead_code)]
 pub const MAGENTA: Color = Color::from_rgb(1.0, 0.2, 0.8); // #ff33cc
 #[allow(dead_code)]
 pub const VIOLET: Color = Color::from_rgb(0.53, 0.53, 1.0); // #8888ff
@@ -31,6 +32,21 @@ pub const CHART_GREEN: Color = GREEN;
 pub const CHART_YELLOW: Color = YELLOW;
 pub const CHART_RED: Color = RED;
 
+/// Chart colour of an attendance status.
+pub fn status_color(status: AttendanceStatus) -> Color {
+    tone_color(status.into())
+}
+
+/// On-screen colour of a chart tone.
+pub fn tone_color(tone: Tone) -> Color {
+    match tone {
+        Tone::Normal => CHART_GREEN,
+        Tone::Late => CHART_YELLOW,
+        Tone::Absent => CHART_RED,
+        Tone::Accent => CYAN,
+    }
+}
+
 // --- Container Styles ---
 
 pub struct ColoredBox(pub Color);
@@ -107,6 +123,22 @@ impl container::StyleSheet for InputGroup {
     }
 }
 
+/// An input group whose value is invalid.
+pub struct InvalidInputGroup;
+impl container::StyleSheet for InvalidInputGroup {
+    type Style = Theme;
+    fn appearance(&self, theme: &Theme) -> container::Appearance {
+        let group = InputGroup.appearance(theme);
+        container::Appearance {
+            border: Border {
+                color: RED,
+                ..group.border
+            },
+            ..group
+        }
+    }
+}
+
 pub struct MainBg;
 impl container::StyleSheet for MainBg {
     type Style = Theme;
@@ -319,6 +351,36 @@ impl button::StyleSheet for PrimaryButton {
     }
 }
 
+// --- Checkbox Styles ---
+
+pub struct Checkbox;
+impl checkbox::StyleSheet for Checkbox {
+    type Style = Theme;
+    fn active(&self, _theme: &Theme, is_checked: bool) -> checkbox::Appearance {
+        checkbox::Appearance {
+            background: Background::Color(if is_checked { BLUE } else { BASE02 }),
+            icon_color: BASE03,
+            border: Border {
+                color: BASE00,
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            text_color: Some(BASE0),
+        }
+    }
+
+    fn hovered(&self, theme: &Theme, is_checked: bool) -> checkbox::Appearance {
+        let active = self.active(theme, is_checked);
+        checkbox::Appearance {
+            border: Border {
+                color: CYAN, // Cyan border hover
+                ..active.border
+            },
+            ..active
+        }
+    }
+}
+
 // --- Scrollable Styles ---
 
 pub struct Scrollable;
@@ -424,3 +486,17 @@ impl menu::StyleSheet for Menu {
         }
     }
 }
+
+// --- Progress Bar Styles ---
+
+pub struct ProgressBar;
+impl progress_bar::StyleSheet for ProgressBar {
+    type Style = Theme;
+    fn appearance(&self, _theme: &Theme) -> progress_bar::Appearance {
+        progress_bar::Appearance {
+            background: Background::Color(BASE03),
+            bar: Background::Color(CYAN),
+            border_radius: 0.0.into(),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use iced::widget::{
+    Column, Row, Space, button, column, container, mouse_area, row, scrollable, text,
+};
+use iced::{Alignment, Element, Length, theme};
+use presence_core::AttendanceReport;
+use presence_core::columns::{self, ExportColumn};
+
+use crate::messages::Message;
+use crate::state::AppState;
+use crate::style;
+
+/// Columns of the class table: every field of the export except the
+/// per-session statuses.
+pub const COLUMNS: [ExportColumn; 14] = [
+    ExportColumn::Surname,
+    ExportColumn::Name,
+    ExportColumn::Id,
+    ExportColumn::Email,
+    ExportColumn::Section,
+    ExportColumn::Normal,
+    ExportColumn::Late,
+    ExportColumn::Absent,
+    ExportColumn::NormalPercent,
+    ExportColumn::LatePercent,
+    ExportColumn::AbsentPercent,
+    ExportColumn::AttendanceRate,
+    ExportColumn::Score,
+    ExportColumn::Eligibility,
+];
+
+/// Narrowest a column can be dragged to.
+pub const MIN_COLUMN_WIDTH: f32 = 48.0;
+
+const RESIZE_HANDLE_WIDTH: f32 = 6.0;
+
+pub fn default_widths() -> Vec<f32> {
+    COLUMNS
+        .iter()
+        .map(|column| column.weight() as f32 * 50.0)
+        .collect()
+}
+
+/// Scroll position of the table body, moved by keyboard navigation.
+pub fn rows_id() -> scrollable::Id {
+    scrollable::Id::new("class-table-rows")
+}
+
+/// Every student matching the list search and filters, one row each. The
+/// header stays in place while the rows scroll; dragging the right edge of a
+/// header cell resizes the column.
+pub fn class_table<'a>(state: &'a AppState, report: &'a AttendanceReport) -> Element<'a, Message> {
+    let widths = &state.column_widths;
+    let header = COLUMNS.iter().zip(widths).enumerate().fold(
+        Row::new().align_items(Alignment::Center),
+        |header, (index, (column, width))| {
+            let handle = mouse_area(
+                container(Space::new(
+                    Length::Fixed(RESIZE_HANDLE_WIDTH),
+                    Length::Fixed(20.0),
+                ))
+                .style(theme::Container::Custom(Box::new(style::ColoredBox(
+                    style::BASE01,
+                )))),
+            )
+            .on_press(Message::ColumnResizeStarted(index));
+            header.push(
+                row![
+                    text(column.default_label())
+                        .size(14)
+                        .style(style::YELLOW)
+                        .width(Length::Fill),
+                    handle,
+                ]
+                .align_items(Alignment::Center)
+                .padding([4, 0, 4, 6])
+                .width(Length::Fixed(*width)),
+            )
+        },
+    );
+
+    let rows = state
+        .visible_students()
+        .into_iter()
+        .fold(Column::new(), |rows, index| {
+            let student = &report.students[index];
+            let cells = COLUMNS.iter().zip(widths).fold(
+                Row::new().align_items(Alignment::Center),
+                |cells, (&column, width)| {
+                    cells.push(
+                        container(text(columns::cell_value(report, student, column)).size(14))
+                            .padding([4, 6])
+                            .width(Length::Fixed(*width)),
+                    )
+                },
+            );
+            let style = if state.selected_student == Some(index) {
+                theme::Button::Custom(Box::new(style::PrimaryButton))
+            } else {
+                theme::Button::Custom(Box::new(style::Button))
+            };
+            rows.push(
+                button(cells)
+                    .on_press(Message::SelectStudent(index))
+                    .style(style)
+                    .padding(0),
+            )
+        });
+
+    let body = scrollable(rows)
+        .id(rows_id())
+        .height(Length::Fill)
+        .style(theme::Scrollable::Custom(Box::new(style::Scrollable)));
+    let table = column![
+        container(header).style(theme::Container::Custom(Box::new(style::BorderedPanel))),
+        body,
+    ]
+    .spacing(4)
+    .width(Length::Fixed(widths.iter().sum()));
+
+    scrollable(table)
+        .direction(scrollable::Direction::Horizontal(
+            scrollable::Properties::new(),
+        ))
+        .height(Length::Fill)
+        .style(theme::Scrollable::Custom(Box::new(style::Scrollable)))
+        .into()
+}
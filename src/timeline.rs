@@ -0,0 +1,309 @@
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::canvas;
+use iced::{Color, Pixels, Point, Rectangle, Renderer, Size, Theme, mouse};
+use presence_core::charts::{self, Guide, Mark, Plot};
+use presence_core::{AttendanceReport, AttendanceStatus, StudentRecord};
+
+use crate::messages::Message;
+use crate::{sessions, style};
+
+/// Height of the mark drawn for a session without lateness, so on-time
+/// sessions stay visible.
+const MIN_BAR_HEIGHT: f32 = 4.0;
+const AXIS_HEIGHT: f32 = 18.0;
+const TOOLTIP_WIDTH: f32 = 220.0;
+const TOOLTIP_LINE_HEIGHT: f32 = 16.0;
+
+/// One session of the timeline: its bar and the details shown on hover.
+struct TimelineBar {
+    mark: Mark,
+    status: AttendanceStatus,
+    original: Option<AttendanceStatus>,
+    join: Option<String>,
+    minutes_late: i64,
+    source: Option<String>,
+}
+
+/// A student's sessions in order, one bar per session coloured by status. The
+/// bar height is the lateness in minutes; sessions the student missed get a
+/// faint full-height bar. Hovering a bar shows its date, join time and export.
+pub struct TimelineChart {
+    max: f32,
+    guides: Vec<Guide>,
+    bars: Vec<TimelineBar>,
+}
+
+impl TimelineChart {
+    pub fn new(report: &AttendanceReport, student: &StudentRecord) -> Self {
+        let Plot::Bars {
+            marks, max, guides, ..
+        } = charts::student_timeline(report, student).plot
+        else {
+            return Self {
+                max: 1.0,
+                guides: Vec::new(),
+                bars: Vec::new(),
+            };
+        };
+        // `student_timeline` draws one mark per session of the history.
+        debug_assert_eq!(marks.len(), student.history.len());
+        let bars = marks
+            .into_iter()
+            .zip(&student.history)
+            .enumerate()
+            .map(|(index, (mark, session))| TimelineBar {
+                mark,
+                status: session.status,
+                original: session.original,
+                join: session
+                    .first_join
+                    .map(|join| join.format("%H:%M").to_string()),
+                minutes_late: session.minutes_late,
+                source: report
+                    .session_sources
+                    .get(index)
+                    .and_then(|source| sessions::source_label(source)),
+            })
+            .collect();
+        Self { max, guides, bars }
+    }
+
+    fn tooltip(bar: &TimelineBar) -> Vec<String> {
+        let status = match bar.original {
+            Some(original) => format!("{}* (was {original})", bar.status),
+            None => bar.status.to_string(),
+        };
+        let join = match &bar.join {
+            Some(join) if bar.minutes_late > 0 => {
+                format!("Joined {join}, {} min late", bar.minutes_late)
+            }
+            Some(join) => format!("Joined {join}"),
+            None => "Did not join".to_string(),
+        };
+        let mut lines = vec![bar.mark.label.clone(), status, join];
+        if let Some(source) = &bar.source {
+            lines.push(source.clone());
+        }
+        lines
+    }
+}
+
+impl canvas::Program<Message> for TimelineChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        if self.bars.is_empty() {
+            frame.fill_text(canvas::Text {
+                content: "No sessions".to_string(),
+                position: Point::new(bounds.width / 2.0, bounds.height / 2.0),
+                color: style::BASE01,
+                size: Pixels(20.0),
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment: Vertical::Center,
+                ..canvas::Text::default()
+            });
+            return vec![frame.into_geometry()];
+        }
+
+        let plot_height = bounds.height - AXIS_HEIGHT;
+        let slot = bounds.width / self.bars.len() as f32;
+        let bar_width = (slot * 0.7).max(1.0);
+        let y_of = |minutes: f32| plot_height - plot_height * (minutes / self.max);
+
+        for guide in &self.guides {
+            let color = style::tone_color(guide.tone);
+            let y = y_of(guide.value);
+            frame.stroke(
+                &canvas::Path::line(Point::new(0.0, y), Point::new(bounds.width, y)),
+                canvas::Stroke {
+                    line_dash: canvas::LineDash {
+                        segments: &[4.0, 4.0],
+                        offset: 0,
+                    },
+                    ..canvas::Stroke::default()
+                        .with_color(Color { a: 0.5, ..color })
+                        .with_width(1.0)
+                },
+            );
+        }
+        frame.stroke(
+            &canvas::Path::line(
+                Point::new(0.0, plot_height),
+                Point::new(bounds.width, plot_height),
+            ),
+            canvas::Stroke::default()
+                .with_color(style::BASE01)
+                .with_width(1.0),
+        );
+
+        let hovered = cursor
+            .position_in(bounds)
+            .filter(|position| position.y <= plot_height)
+            .map(|position| ((position.x / slot) as usize).min(self.bars.len() - 1));
+
+        for (index, mark) in self.bars.iter().map(|bar| &bar.mark).enumerate() {
+            let x = index as f32 * slot + (slot - bar_width) / 2.0;
+            let color = style::tone_color(mark.tone);
+            let (height, color) = if mark.faint {
+                (plot_height - y_of(mark.value), Color { a: 0.35, ..color })
+            } else {
+                let height = plot_height - y_of(mark.value);
+                (height.max(MIN_BAR_HEIGHT), color)
+            };
+            let top_left = Point::new(x, plot_height - height);
+            let size = Size::new(bar_width, height);
+            frame.fill_rectangle(top_left, size, color);
+            if mark.marked || hovered == Some(index) {
+                let outline = if mark.marked {
+                    style::MAGENTA
+                } else {
+                    style::BASE1
+                };
+                frame.stroke(
+                    &canvas::Path::rectangle(top_left, size),
+                    canvas::Stroke::default()
+                        .with_color(outline)
+                        .with_width(2.0),
+                );
+            }
+        }
+
+        let first = &self.bars[0].mark.label;
+        let last = &self.bars[self.bars.len() - 1].mark.label;
+        for (content, x, alignment) in [
+            (first, 0.0, Horizontal::Left),
+            (last, bounds.width, Horizontal::Right),
+        ] {
+            frame.fill_text(canvas::Text {
+                content: content.clone(),
+                position: Point::new(x, bounds.height),
+                color: style::BASE00,
+                size: Pixels(12.0),
+                horizontal_alignment: alignment,
+                vertical_alignment: Vertical::Bottom,
+                ..canvas::Text::default()
+            });
+        }
+
+        if let Some(index) = hovered
+            && let Some(bar) = self.bars.get(index)
+            && let Some(position) = cursor.position_in(bounds)
+        {
+            let lines = Self::tooltip(bar);
+            let size = Size::new(
+                TOOLTIP_WIDTH,
+                lines.len() as f32 * TOOLTIP_LINE_HEIGHT + 12.0,
+            );
+            // Keep the box inside the chart, flipping it left of the cursor
+            // near the right edge.
+            let x = if position.x + 12.0 + size.width > bounds.width {
+                (position.x - 12.0 - size.width).max(0.0)
+            } else {
+                position.x + 12.0
+            };
+            let y = position.y.min(bounds.height - size.height).max(0.0);
+            let origin = Point::new(x, y);
+            frame.fill_rectangle(origin, size, style::BASE02);
+            frame.stroke(
+                &canvas::Path::rectangle(origin, size),
+                canvas::Stroke::default()
+                    .with_color(style::BASE01)
+                    .with_width(1.0),
+            );
+            for (line, content) in lines.into_iter().enumerate() {
+                frame.fill_text(canvas::Text {
+                    content,
+                    position: Point::new(
+                        origin.x + 8.0,
+                        origin.y + 6.0 + line as f32 * TOOLTIP_LINE_HEIGHT,
+                    ),
+                    color: if line == 0 {
+                        style::BASE1
+                    } else {
+                        style::BASE0
+                    },
+                    size: Pixels(12.0),
+                    ..canvas::Text::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use presence_core::profile::Profile;
+
+    #[test]
+    fn test_tooltip_lines() {
+        let mut report = presence_core::generate_report(
+            [
+                "Name,Email,First Join\nJohn Doe,john@example.com,\"10/25/23, 1:45:00 PM\"",
+                "Name,Email,First Join\nJohn Doe,john@example.com,\"10/26/23, 1:30:00 PM\"",
+                "Name,Email,First Join\nJane Roe,jane@example.com,\"10/27/23, 1:30:00 PM\"",
+            ]
+            .iter()
+            .map(|csv| presence_core::parse_participants(csv.as_bytes(), "csv").unwrap())
+            .collect(),
+            Profile::default().to_config(),
+        )
+        .unwrap();
+        report.session_sources = vec![
+            "exports/week-01.csv".into(),
+            "exports/term.zip/week-02.csv".into(),
+        ];
+        let mut john = report
+            .students
+            .iter()
+            .find(|student| student.email == "john@example.com")
+            .unwrap()
+            .clone();
+        john.history[1].original = Some(AttendanceStatus::Absent);
+
+        let chart = TimelineChart::new(&report, &john);
+        let lines: Vec<Vec<String>> = chart.bars.iter().map(TimelineChart::tooltip).collect();
+        assert_eq!(
+            lines,
+            [
+                vec![
+                    "2023-10-25",
+                    "Late",
+                    "Joined 13:45, 15 min late",
+                    "week-01.csv"
+                ],
+                vec![
+                    "2023-10-26",
+                    "Normal* (was Absent)",
+                    "Joined 13:30",
+                    "term.zip: week-02.csv"
+                ],
+                // No source recorded for the last session
+                vec!["2023-10-27", "Absent", "Did not join"],
+            ]
+        );
+    }
+}
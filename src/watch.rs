@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use iced::Subscription;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::subscription;
+use presence_core::watch::SourceWatcher;
+
+use crate::messages::Message;
+use crate::state::user_message;
+
+/// Emits `Message::SourceChanged` whenever something changes in an attendance
+/// source. The watcher lives as long as the subscription, which iced keeps
+/// running while `source` and `recursive` stay the same.
+pub fn source_changes(source: PathBuf, recursive: bool) -> Subscription<Message> {
+    let id = (source.clone(), recursive);
+    subscription::channel(id, 16, move |mut output| async move {
+        let (sender, mut receiver) = mpsc::unbounded();
+        match SourceWatcher::new(&source, recursive, move || {
+            let _ = sender.unbounded_send(());
+        }) {
+            Ok(_watcher) => {
+                while receiver.next().await.is_some() {
+                    let _ = output.send(Message::SourceChanged).await;
+                }
+            }
+            Err(error) => {
+                let _ = output
+                    .send(Message::WatchFailed(user_message(&error)))
+                    .await;
+            }
+        }
+        iced::futures::future::pending().await
+    })
+}